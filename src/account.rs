@@ -0,0 +1,459 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::address::validate;
+use crate::batch::signer_address;
+use crate::credit::{CREDIT_MANAGER, RED_BANK};
+use crate::denom::DenomRegistry;
+use crate::error::{Error, Result};
+use crate::market::{decimal, price, tvl_unit, Market, ORACLE};
+use crate::network::Network;
+use crate::output::Output;
+use crate::runner::Runner;
+use crate::{get_contract_address, smart_query, Fund};
+
+pub static ACCOUNT_NFT: &str = "accountNft";
+
+/// The part of the creditManager `config` naming the contracts it uses.
+/// Older deployments may leave them out.
+#[derive(Deserialize, Debug, Default)]
+struct Config {
+    #[serde(default)]
+    red_bank: Option<String>,
+    #[serde(default)]
+    oracle: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Positions {
+    #[serde(default, alias = "coins")]
+    deposits: Vec<Fund>,
+    #[serde(default)]
+    lends: Vec<Fund>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DebtAmount {
+    denom: String,
+    shares: String,
+    amount: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TokensResponse {
+    tokens: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Deposit,
+    Lend,
+    Debt,
+}
+
+impl AssetKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AssetKind::Deposit => "deposit",
+            AssetKind::Lend => "lend",
+            AssetKind::Debt => "debt",
+        }
+    }
+}
+
+/// One coin of a credit account with its oracle price and the market
+/// parameters it is weighted with.
+#[derive(Serialize, Debug)]
+pub struct AssetReport {
+    pub kind: AssetKind,
+    pub denom: String,
+    pub amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shares: Option<String>,
+    pub price: Option<String>,
+    pub value: Option<f64>,
+    pub max_loan_to_value: String,
+    pub liquidation_threshold: String,
+}
+
+/// What `account <id>` shows. Values are in `value_unit`, the unit of the
+/// `markets` TVL, and leave out assets the oracle has no price for; the
+/// ratios are `None` without collateral or debt to divide by.
+#[derive(Serialize, Debug)]
+pub struct AccountReport {
+    pub account_id: String,
+    pub red_bank: String,
+    pub oracle: String,
+    pub assets: Vec<AssetReport>,
+    pub value_unit: String,
+    pub collateral_value: f64,
+    pub debt_value: f64,
+    pub ltv: Option<f64>,
+    pub liquidation_threshold: Option<f64>,
+    pub health_factor: Option<f64>,
+}
+
+/// What `accounts` shows: the credit accounts of `owner` and, when asked
+/// for, the gist of each one's position.
+#[derive(Serialize, Debug)]
+pub struct AccountsReport {
+    pub owner: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_label: Option<String>,
+    /// The unit of the position values, when there are any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_unit: Option<String>,
+    pub accounts: Vec<AccountSummary>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AccountSummary {
+    pub account_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<PositionSummary>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PositionSummary {
+    pub collateral_value: f64,
+    pub debt_value: f64,
+    pub health_factor: Option<f64>,
+}
+
+/// Queries the positions and debts of a credit account, prices every coin
+/// with the oracle and weighs the collateral with the redbank market
+/// parameters. The oracle and redbank are the ones in the creditManager
+/// config, or the network's contracts of those names.
+pub fn account_report(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    account_id: &str,
+) -> Result<AccountReport> {
+    let credit_manager = get_contract_address(network, CREDIT_MANAGER)?;
+    let config: Config = smart_query(runner, network, &credit_manager, &json!({"config": {}}))?;
+    let red_bank = match config.red_bank {
+        Some(red_bank) => red_bank,
+        None => get_contract_address(network, RED_BANK)?,
+    };
+    let oracle = match config.oracle {
+        Some(oracle) => oracle,
+        None => get_contract_address(network, ORACLE)?,
+    };
+
+    let positions: Positions = smart_query(
+        runner,
+        network,
+        &credit_manager,
+        &json!({"positions": {"account_id": account_id}}),
+    )?;
+    let debts: Vec<DebtAmount> = smart_query(
+        runner,
+        network,
+        &credit_manager,
+        &json!({"debt_shares_amounts": {"account_id": account_id}}),
+    )?;
+
+    let coins = positions
+        .deposits
+        .into_iter()
+        .map(|fund| (AssetKind::Deposit, fund, None))
+        .chain(
+            positions
+                .lends
+                .into_iter()
+                .map(|fund| (AssetKind::Lend, fund, None)),
+        )
+        .chain(debts.into_iter().map(|debt| {
+            let fund = Fund {
+                denom: debt.denom,
+                amount: debt.amount,
+            };
+            (AssetKind::Debt, fund, Some(debt.shares))
+        }));
+
+    let (value_unit, unit_value) = tvl_unit(runner, network, denoms, &oracle)?;
+
+    let mut prices: HashMap<String, Option<String>> = HashMap::new();
+    let mut markets: HashMap<String, Market> = HashMap::new();
+    let mut assets = Vec::new();
+    for (kind, fund, shares) in coins {
+        if !prices.contains_key(&fund.denom) {
+            let price = price(runner, network, &oracle, &fund.denom)?;
+            prices.insert(fund.denom.clone(), price);
+        }
+        if !markets.contains_key(&fund.denom) {
+            let market: Market = smart_query(
+                runner,
+                network,
+                &red_bank,
+                &json!({"market": {"denom": fund.denom}}),
+            )?;
+            markets.insert(fund.denom.clone(), market);
+        }
+
+        let price = prices[&fund.denom].clone();
+        let market = markets[&fund.denom].clone();
+        assets.push(AssetReport {
+            kind,
+            display: denoms.display(&fund.amount, &fund.denom),
+            value: match &price {
+                Some(price) => Some(decimal(&fund.amount)? * decimal(price)? / unit_value),
+                None => None,
+            },
+            denom: fund.denom,
+            amount: fund.amount,
+            shares,
+            price,
+            max_loan_to_value: market.max_loan_to_value,
+            liquidation_threshold: market.liquidation_threshold,
+        });
+    }
+
+    let mut collateral_value = 0.0;
+    let mut threshold_value = 0.0;
+    let mut debt_value = 0.0;
+    for asset in &assets {
+        let value = match asset.value {
+            Some(value) => value,
+            None => continue,
+        };
+        if asset.kind == AssetKind::Debt {
+            debt_value += value;
+        } else {
+            collateral_value += value;
+            threshold_value += value * decimal(&asset.liquidation_threshold)?;
+        }
+    }
+
+    let ratio =
+        |numerator: f64, denominator: f64| (denominator > 0.0).then(|| numerator / denominator);
+    Ok(AccountReport {
+        account_id: account_id.to_owned(),
+        red_bank,
+        oracle,
+        assets,
+        value_unit,
+        collateral_value,
+        debt_value,
+        ltv: ratio(debt_value, collateral_value),
+        liquidation_threshold: ratio(threshold_value, collateral_value),
+        health_factor: ratio(threshold_value, debt_value),
+    })
+}
+
+/// Prints the assets as a table followed by the account's totals and ratios.
+pub fn print_account(report: &AccountReport, out: &mut Output) -> Result<()> {
+    if !out.is_text() {
+        return out.emit(report);
+    }
+
+    writeln!(out, "___ Credit account {} ___", report.account_id)?;
+
+    let header = [
+        "kind",
+        "denom",
+        "amount",
+        "price",
+        &format!("value ({})", report.value_unit),
+        "max LTV",
+        "liq. threshold",
+    ]
+    .map(str::to_owned)
+    .to_vec();
+    let rows: Vec<Vec<String>> = std::iter::once(header)
+        .chain(report.assets.iter().map(|asset| {
+            vec![
+                asset.kind.as_str().to_owned(),
+                asset.denom.clone(),
+                match &asset.display {
+                    Some(display) => format!("{} ({})", asset.amount, display),
+                    None => asset.amount.clone(),
+                },
+                asset.price.clone().unwrap_or_else(|| "-".to_owned()),
+                match asset.value {
+                    Some(value) => format!("{:.2}", value),
+                    None => "-".to_owned(),
+                },
+                asset.max_loan_to_value.clone(),
+                asset.liquidation_threshold.clone(),
+            ]
+        }))
+        .collect();
+    out.write_table(&rows)?;
+
+    let ratio = |ratio: Option<f64>| match ratio {
+        Some(ratio) => format!("{:.4}", ratio),
+        None => "-".to_owned(),
+    };
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Collateral value:      {:.2} {}",
+        report.collateral_value, report.value_unit
+    )?;
+    writeln!(
+        out,
+        "Debt value:            {:.2} {}",
+        report.debt_value, report.value_unit
+    )?;
+    writeln!(out, "LTV:                   {}", ratio(report.ltv))?;
+    writeln!(
+        out,
+        "Liquidation threshold: {}",
+        ratio(report.liquidation_threshold)
+    )?;
+    writeln!(
+        out,
+        "Health factor:         {}",
+        ratio(report.health_factor)
+    )?;
+
+    Ok(())
+}
+
+/// Lists the credit accounts owned by `owner`, a name from the address book
+/// or an address, or by the network's signer without one. Follows the
+/// accountNft `tokens` pages `page_size` at a time. With `positions`, each
+/// account is summarized from its [`account_report`].
+pub fn accounts_report(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    owner: Option<&str>,
+    page_size: u32,
+    positions: bool,
+) -> Result<AccountsReport> {
+    let owner = match owner {
+        None => signer_address(runner, network)?,
+        Some(owner) => match network.address_book()?.address(owner) {
+            Some(address) => address.to_owned(),
+            None => {
+                validate(&network.prefix, owner).map_err(|e| {
+                    Error::config(format!("Unknown name or invalid address {}: {}", owner, e))
+                })?;
+                owner.to_owned()
+            }
+        },
+    };
+    let account_nft = get_contract_address(network, ACCOUNT_NFT)?;
+
+    let mut ids: Vec<String> = Vec::new();
+    loop {
+        let mut query = json!({"tokens": {"owner": owner, "limit": page_size}});
+        if let Some(last) = ids.last() {
+            query["tokens"]["start_after"] = Value::String(last.clone());
+        }
+
+        // accountNft caps `limit`, so only an empty page is the last one.
+        let page: TokensResponse = smart_query(runner, network, &account_nft, &query)?;
+        if page.tokens.is_empty() {
+            break;
+        }
+        ids.extend(page.tokens);
+    }
+
+    let mut value_unit = None;
+    let accounts = ids
+        .into_iter()
+        .map(|account_id| {
+            let position = if positions {
+                let report = account_report(runner, network, denoms, &account_id)?;
+                value_unit = Some(report.value_unit);
+                Some(PositionSummary {
+                    collateral_value: report.collateral_value,
+                    debt_value: report.debt_value,
+                    health_factor: report.health_factor,
+                })
+            } else {
+                None
+            };
+
+            Ok(AccountSummary {
+                account_id,
+                position,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(AccountsReport {
+        owner_label: network
+            .address_book()?
+            .name(&owner)
+            .map(|name| name.to_owned()),
+        owner,
+        value_unit,
+        accounts,
+    })
+}
+
+/// Prints the account ids, one per line, or a table of their positions.
+pub fn print_accounts(report: &AccountsReport, out: &mut Output) -> Result<()> {
+    if !out.is_text() {
+        return out.emit(report);
+    }
+
+    match &report.owner_label {
+        Some(label) => writeln!(
+            out,
+            "___ Credit accounts of {} ({}): {} ___",
+            report.owner,
+            label,
+            report.accounts.len()
+        )?,
+        None => writeln!(
+            out,
+            "___ Credit accounts of {}: {} ___",
+            report.owner,
+            report.accounts.len()
+        )?,
+    }
+
+    if report
+        .accounts
+        .iter()
+        .all(|account| account.position.is_none())
+    {
+        for account in &report.accounts {
+            writeln!(out, "{}", account.account_id)?;
+        }
+        return Ok(());
+    }
+
+    let unit = report.value_unit.as_deref().unwrap_or("-");
+    let header = [
+        "account",
+        &format!("collateral value ({})", unit),
+        &format!("debt value ({})", unit),
+        "health factor",
+    ]
+    .map(str::to_owned)
+    .to_vec();
+    let rows: Vec<Vec<String>> = std::iter::once(header)
+        .chain(
+            report
+                .accounts
+                .iter()
+                .map(|account| match &account.position {
+                    Some(position) => vec![
+                        account.account_id.clone(),
+                        format!("{:.2}", position.collateral_value),
+                        format!("{:.2}", position.debt_value),
+                        match position.health_factor {
+                            Some(health_factor) => format!("{:.4}", health_factor),
+                            None => "-".to_owned(),
+                        },
+                    ],
+                    None => vec![account.account_id.clone()],
+                }),
+        )
+        .collect();
+
+    out.write_table(&rows)
+}
@@ -0,0 +1,141 @@
+use serde::Serialize;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::address::{AddressBook, Kind};
+use crate::error::{Error, Result};
+use crate::output::Output;
+
+/// Root of the message files, laid out as `<contract>/<kind>-<action>.json`.
+pub static MESSAGES: &str = "json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Execute,
+    Query,
+}
+
+impl MessageKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            MessageKind::Execute => "execute-",
+            MessageKind::Query => "query-",
+        }
+    }
+}
+
+/// The actions that have a message file, per contract.
+#[derive(Serialize, Debug)]
+pub struct ContractActions {
+    pub contract: String,
+    pub execute: Vec<String>,
+    pub query: Vec<String>,
+}
+
+/// Path of the message file for `action`, e.g. `json/redbank/execute-deposit.json`.
+/// Fails when there is no such file.
+pub fn action_path(dir: &str, contract: &str, kind: MessageKind, action: &str) -> Result<String> {
+    let path = Path::new(dir)
+        .join(contract)
+        .join(format!("{}{}.json", kind.prefix(), action));
+
+    if !path.is_file() {
+        return Err(Error::config(format!(
+            "No {}{} action for {}: {} does not exist",
+            kind.prefix(),
+            action,
+            contract,
+            path.display()
+        )));
+    }
+
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Lists the actions of every contract directory under `dir`, or only of
+/// `contract`. Each directory has to be named after a contract in the
+/// address book.
+pub fn list_actions(
+    dir: &str,
+    book: &AddressBook,
+    contract: Option<&str>,
+) -> Result<Vec<ContractActions>> {
+    let mut contracts: Vec<String> = read_dir(dir)?
+        .into_iter()
+        .filter(|name| Path::new(dir).join(name).is_dir())
+        .filter(|name| contract.is_none_or(|contract| contract == name))
+        .collect();
+    contracts.sort();
+
+    if let Some(contract) = contract {
+        if contracts.is_empty() {
+            return Err(Error::config(format!(
+                "No messages for {} in {}",
+                contract, dir
+            )));
+        }
+    }
+
+    let unknown: Vec<&str> = contracts
+        .iter()
+        .filter(|name| book.lookup(name).map(|entry| entry.kind) != Some(Kind::Contract))
+        .map(|name| name.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(Error::config(format!(
+            "Message directories without a contract in the contracts file: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    contracts
+        .into_iter()
+        .map(|contract| {
+            let files = read_dir(&Path::new(dir).join(&contract).to_string_lossy())?;
+            Ok(ContractActions {
+                execute: actions(&files, MessageKind::Execute),
+                query: actions(&files, MessageKind::Query),
+                contract,
+            })
+        })
+        .collect()
+}
+
+/// Prints one block per contract with its execute and query actions.
+pub fn print_actions(actions: &[ContractActions], out: &mut Output) -> Result<()> {
+    if !out.is_text() {
+        return out.emit(&actions);
+    }
+
+    for contract in actions {
+        writeln!(out, "{}", contract.contract)?;
+        writeln!(out, "  execute: {}", contract.execute.join(", "))?;
+        writeln!(out, "  query: {}", contract.query.join(", "))?;
+    }
+
+    Ok(())
+}
+
+fn actions(files: &[String], kind: MessageKind) -> Vec<String> {
+    let mut actions: Vec<String> = files
+        .iter()
+        .filter_map(|file| file.strip_prefix(kind.prefix())?.strip_suffix(".json"))
+        // Skips the `*.vars.json` defaults next to the messages.
+        .filter(|action| !action.ends_with(".vars"))
+        .map(|action| action.to_owned())
+        .collect();
+    actions.sort();
+    actions
+}
+
+fn read_dir(dir: &str) -> Result<Vec<String>> {
+    fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                .collect()
+        })
+        .map_err(|e| Error::io(format!("Unable to read message directory {}", dir), e))
+}
@@ -0,0 +1,297 @@
+use bech32::{FromBase32, ToBase32, Variant};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::output::Output;
+
+pub static ADDRESSES: &str = "config/addresses.json";
+
+/// Module accounts worth naming in tx output. Their addresses are derived
+/// from the module name, so they are the same on every network.
+pub static MODULE_ACCOUNTS: &[&str] = &[
+    "fee_collector",
+    "distribution",
+    "mint",
+    "bonded_tokens_pool",
+    "not_bonded_tokens_pool",
+    "gov",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Contract,
+    Wallet,
+    Module,
+}
+
+impl Kind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Contract => "contract",
+            Kind::Wallet => "wallet",
+            Kind::Module => "module",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub address: String,
+    pub kind: Kind,
+}
+
+/// Every address we can put a name to: the network's contracts, the named
+/// wallets from its addresses file and the well-known module accounts.
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    entries: Vec<Entry>,
+    by_name: HashMap<String, usize>,
+    by_address: HashMap<String, usize>,
+}
+
+impl AddressBook {
+    /// Loads the contracts and wallets files of `network`, checking every
+    /// address is valid bech32 with the network's prefix and that no name or
+    /// address is listed twice. The wallets file is optional.
+    ///
+    /// Use [`Network::address_book`] to load it once per network.
+    pub fn load(network: &Network) -> Result<Self> {
+        let mut entries = read_entries(&network.contracts, Kind::Contract, &network.prefix)?;
+
+        if Path::new(&network.addresses).exists() {
+            entries.extend(read_entries(
+                &network.addresses,
+                Kind::Wallet,
+                &network.prefix,
+            )?);
+        }
+
+        for name in MODULE_ACCOUNTS {
+            entries.push(Entry {
+                name: (*name).to_owned(),
+                address: module_address(&network.prefix, name)?,
+                kind: Kind::Module,
+            });
+        }
+
+        AddressBook::new(entries)
+    }
+
+    /// Indexes entries by name and address, refusing duplicates of either.
+    pub fn new(entries: Vec<Entry>) -> Result<Self> {
+        let mut book = AddressBook::default();
+
+        for entry in entries {
+            let index = book.entries.len();
+            if let Some(&other) = book.by_name.get(&entry.name) {
+                return Err(Error::config(format!(
+                    "Duplicate name {}: {} {} and {} {}",
+                    entry.name,
+                    book.entries[other].kind.as_str(),
+                    book.entries[other].address,
+                    entry.kind.as_str(),
+                    entry.address
+                )));
+            }
+            if let Some(&other) = book.by_address.get(&entry.address) {
+                return Err(Error::config(format!(
+                    "Duplicate address {}: named both {} and {}",
+                    entry.address, book.entries[other].name, entry.name
+                )));
+            }
+
+            book.by_name.insert(entry.name.clone(), index);
+            book.by_address.insert(entry.address.clone(), index);
+            book.entries.push(entry);
+        }
+
+        Ok(book)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn name(&self, address: &str) -> Option<&str> {
+        self.by_address
+            .get(address)
+            .map(|&i| self.entries[i].name.as_str())
+    }
+
+    pub fn address(&self, name: &str) -> Option<&str> {
+        self.by_name
+            .get(name)
+            .map(|&i| self.entries[i].address.as_str())
+    }
+
+    /// Address of the contract called `name`; wallets and module accounts
+    /// do not count.
+    pub fn contract_address(&self, name: &str) -> Result<&str> {
+        self.by_name
+            .get(name)
+            .map(|&i| &self.entries[i])
+            .filter(|entry| entry.kind == Kind::Contract)
+            .map(|entry| entry.address.as_str())
+            .ok_or_else(|| Error::config(format!("Invalid contract name: {}", name)))
+    }
+
+    /// Finds an entry by name or by address.
+    pub fn lookup(&self, name_or_address: &str) -> Option<&Entry> {
+        self.by_name
+            .get(name_or_address)
+            .or_else(|| self.by_address.get(name_or_address))
+            .map(|&i| &self.entries[i])
+    }
+}
+
+/// Address of a module account: the first 20 bytes of the SHA-256 of its
+/// name, bech32 encoded. Fails when `prefix` is not a valid bech32 prefix.
+pub fn module_address(prefix: &str, module: &str) -> Result<String> {
+    let hash = Sha256::digest(module.as_bytes());
+    bech32::encode(prefix, (&hash[..20]).to_base32(), Variant::Bech32)
+        .map_err(|e| Error::config(format!("Invalid address prefix {:?}: {}", prefix, e)))
+}
+
+/// Checks `address` is bech32 with the checksum intact and `prefix` as its
+/// human readable part.
+pub fn validate(prefix: &str, address: &str) -> std::result::Result<(), String> {
+    let (hrp, data, variant) = bech32::decode(address).map_err(|e| e.to_string())?;
+
+    if hrp != prefix {
+        return Err(format!("expected prefix {}, got {}", prefix, hrp));
+    }
+    if variant != Variant::Bech32 {
+        return Err("expected bech32, got bech32m".to_owned());
+    }
+    Vec::<u8>::from_base32(&data).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Adds a named wallet to the network's addresses file and its cached
+/// address book. Names and addresses already used by a contract, module
+/// account or another wallet are refused.
+pub fn add_wallet(network: &mut Network, name: &str, address: &str) -> Result<Entry> {
+    validate(&network.prefix, address)
+        .map_err(|e| Error::config(format!("Invalid address {}: {}", address, e)))?;
+
+    let book = network.address_book()?;
+    if let Some(existing) = book.lookup(name).filter(|entry| entry.name == name) {
+        return Err(Error::config(format!(
+            "{} is already the {} {}",
+            name,
+            existing.kind.as_str(),
+            existing.address
+        )));
+    }
+    if let Some(existing) = book
+        .lookup(address)
+        .filter(|entry| entry.address == address)
+    {
+        return Err(Error::config(format!(
+            "{} is already the {} {}",
+            address,
+            existing.kind.as_str(),
+            existing.name
+        )));
+    }
+
+    let entry = Entry {
+        name: name.to_owned(),
+        address: address.to_owned(),
+        kind: Kind::Wallet,
+    };
+    let mut entries = book.entries().to_vec();
+    entries.push(entry.clone());
+    let book = AddressBook::new(entries)?;
+
+    let wallets: Map<String, Value> = book
+        .entries()
+        .iter()
+        .filter(|entry| entry.kind == Kind::Wallet)
+        .map(|entry| (entry.name.clone(), Value::String(entry.address.clone())))
+        .collect();
+
+    let mut json = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut json, formatter);
+    wallets
+        .serialize(&mut serializer)
+        .map_err(|e| Error::json("Failed to format addresses file", e))?;
+    fs::write(&network.addresses, json).map_err(|e| {
+        Error::io(
+            format!("Failed to write addresses file {}", network.addresses),
+            e,
+        )
+    })?;
+    network.set_address_book(book);
+
+    Ok(entry)
+}
+
+/// Prints entries as a `kind  name  address` table.
+pub fn print_entries(entries: &[Entry], out: &mut Output) -> Result<()> {
+    if !out.is_text() {
+        return out.emit(&entries);
+    }
+
+    let width = entries
+        .iter()
+        .map(|entry| entry.name.len())
+        .max()
+        .unwrap_or(0);
+    for entry in entries {
+        writeln!(
+            out,
+            "{:8}  {:width$}  {}",
+            entry.kind.as_str(),
+            entry.name,
+            entry.address,
+            width = width
+        )?;
+    }
+
+    Ok(())
+}
+
+fn read_entries(path: &str, kind: Kind, prefix: &str) -> Result<Vec<Entry>> {
+    read_object(path, kind)?
+        .into_iter()
+        .map(|(name, value)| {
+            let address = value.as_str().ok_or_else(|| {
+                Error::config(format!("Address of {} in {} is not a string", name, path))
+            })?;
+            validate(prefix, address).map_err(|e| {
+                Error::config(format!("Invalid address for {} in {}: {}", name, path, e))
+            })?;
+
+            Ok(Entry {
+                name,
+                address: address.to_owned(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+fn read_object(path: &str, kind: Kind) -> Result<Map<String, Value>> {
+    let file = match kind {
+        Kind::Contract => "contracts file",
+        _ => "addresses file",
+    };
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Error::io(format!("Unable to read {} {}", file, path), e))?;
+
+    serde_json::from_str(&contents)
+        .map_err(|e| Error::json(format!("Unable to parse {} {}", file, path), e))
+}
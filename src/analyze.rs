@@ -0,0 +1,712 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+
+use std::io::Write;
+
+use crate::address::AddressBook;
+use crate::batch::format_coins;
+use crate::denom::{parse_base_coins, DenomRegistry};
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::output::Output;
+use crate::{Attribute, Data, Event, Message};
+
+/// Everything `analyze` knows about a tx, in the stable shape emitted by
+/// `--output json|yaml`.
+#[derive(Serialize, Debug)]
+pub struct TxReport {
+    pub hash: String,
+    pub code: i32,
+    pub codespace: String,
+    pub sender: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_label: Option<String>,
+    pub format: TxFormat,
+    pub messages: Vec<MessageReport>,
+    pub events: Vec<EventReport>,
+    pub logs: Vec<LogReport>,
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+/// Net change of one denom in one address's balance over the whole tx, fee
+/// included. `amount` is signed, e.g. `-6250`.
+#[derive(Serialize, Debug, Clone)]
+pub struct BalanceChange {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub denom: String,
+    pub amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+/// Shape of the `query tx` response the report was built from.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TxFormat {
+    /// Per-message `logs`, top-level event attributes base64 encoded.
+    Legacy,
+    /// SDK 0.50: no `logs`, plain attributes and a `msg_index` attribute on
+    /// every event emitted by a message.
+    Sdk50,
+}
+
+impl TxFormat {
+    pub fn detect(data: &Data) -> TxFormat {
+        let tagged = data
+            .events
+            .iter()
+            .flat_map(|event| &event.attributes)
+            .any(|attribute| attribute.key == MSG_INDEX);
+
+        if data.logs.is_empty() && tagged {
+            TxFormat::Sdk50
+        } else {
+            TxFormat::Legacy
+        }
+    }
+}
+
+static MSG_INDEX: &str = "msg_index";
+
+/// A tx message with its position and the name of the contract it calls.
+#[derive(Serialize, Debug)]
+pub struct MessageReport {
+    pub msg_index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The funds in display units, e.g. `1.5 OSMO`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_funds: Option<String>,
+    #[serde(flatten)]
+    pub message: Message,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EventReport {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub attributes: Vec<AttributeReport>,
+}
+
+/// A decoded event attribute. `label` names the value when it is an address
+/// in the address book, or gives coin amounts in display units.
+#[derive(Serialize, Debug, Clone)]
+pub struct AttributeReport {
+    pub key: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LogReport {
+    pub msg_index: u32,
+    pub events: Vec<EventReport>,
+    /// Contracts executed by the message, in call order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallReport>,
+}
+
+/// One contract execution. `depth` is 0 for the contract the message called
+/// and 1 for every execution after it: events do not say which contract
+/// dispatched a submessage, so a sub-submessage is at depth 1 as well.
+#[derive(Serialize, Debug)]
+pub struct CallReport {
+    pub contract: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub depth: u32,
+    pub events: Vec<WasmEventReport>,
+}
+
+/// A `wasm` or `wasm-*` event without its `_contract_address` and `action`.
+#[derive(Serialize, Debug)]
+pub struct WasmEventReport {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    pub attributes: Vec<AttributeReport>,
+}
+
+/// Builds the report for a tx. The top-level events of a legacy response are
+/// base64 decoded, but only if every one of their attributes decodes to
+/// printable text: a single plain attribute means the node did not encode
+/// them and decoding would turn values such as `1000` into garbage. With
+/// `raw_events` the events are reported as received, but balance changes
+/// are still read from the decoded ones.
+pub fn tx_report(
+    network: &Network,
+    denoms: &DenomRegistry,
+    data: Data,
+    raw_events: bool,
+) -> Result<TxReport> {
+    let sender = data
+        .tx
+        .body
+        .messages
+        .first()
+        .map(|message| message.sender.clone())
+        .unwrap_or_default();
+
+    let format = TxFormat::detect(&data);
+    let decoded = match format {
+        TxFormat::Legacy => decode_all(&data.events),
+        _ => None,
+    };
+    let book = network.address_book()?;
+    let decoded_events = label_events(book, denoms, decoded.as_deref().unwrap_or(&data.events));
+    let balance_changes = balance_changes(&decoded_events, denoms);
+    let events = if raw_events {
+        label_events(book, denoms, &data.events)
+    } else {
+        decoded_events
+    };
+    let logs = match format {
+        TxFormat::Legacy => data
+            .logs
+            .iter()
+            .map(|log| {
+                let events = label_events(book, denoms, &log.events);
+                LogReport {
+                    msg_index: log.msg_index,
+                    calls: wasm_calls(&events),
+                    events,
+                }
+            })
+            .collect(),
+        TxFormat::Sdk50 => group_by_msg_index(&events),
+    };
+
+    let messages = data
+        .tx
+        .body
+        .messages
+        .into_iter()
+        .enumerate()
+        .map(|(i, message)| MessageReport {
+            msg_index: i as u32,
+            label: book.name(&message.contract).map(|name| name.to_owned()),
+            display_funds: denoms.display_coins(&format_coins(&message.funds)),
+            message,
+        })
+        .collect();
+
+    Ok(TxReport {
+        hash: data.txhash,
+        code: data.code,
+        codespace: data.codespace,
+        sender_label: book.name(&sender).map(|name| name.to_owned()),
+        sender,
+        format,
+        messages,
+        balance_changes,
+        events,
+        logs,
+    })
+}
+
+pub fn print_tx_details(
+    network: &Network,
+    denoms: &DenomRegistry,
+    data: Data,
+    raw_events: bool,
+    out: &mut Output,
+) -> Result<()> {
+    let report = tx_report(network, denoms, data, raw_events)?;
+
+    if !out.is_text() {
+        return out.emit(&report);
+    }
+
+    print_report(&report, out)
+}
+
+/// Prints a report as the `___ Section ___` text layout.
+pub fn print_report(report: &TxReport, out: &mut Output) -> Result<()> {
+    let events_short: String = summarize_events(&report.events);
+
+    writeln!(out, "___ Tx hash ___")?;
+    writeln!(out, "{}", report.hash)?;
+
+    writeln!(out, "___ Sender ___")?;
+    match &report.sender_label {
+        Some(label) => writeln!(out, "{} ({})", report.sender, label)?,
+        None => writeln!(out, "{}", report.sender)?,
+    }
+
+    // Each message next to the log it produced, matched on msg_index.
+    for message in &report.messages {
+        match &message.label {
+            Some(label) => writeln!(out, "___ Message {}: {} ___", message.msg_index, label)?,
+            None => writeln!(out, "___ Message {} ___", message.msg_index)?,
+        }
+
+        let json_str = serde_json::to_string_pretty(&message.message)
+            .map_err(|e| Error::json("Failed to format message", e))?;
+        writeln!(out, "Message:\n{}", json_str)?;
+        if let Some(display_funds) = &message.display_funds {
+            writeln!(out, "Funds: {}", display_funds)?;
+        }
+
+        match report
+            .logs
+            .iter()
+            .find(|log| log.msg_index == message.msg_index)
+        {
+            Some(log) => {
+                writeln!(out, "Logs:\n{}", summarize_events(&log.events))?;
+                if !log.calls.is_empty() {
+                    writeln!(out, "Contracts:\n{}", summarize_calls(&log.calls))?;
+                }
+            }
+            None => writeln!(out, "Logs: none\n")?,
+        }
+    }
+
+    writeln!(out, "___ Events ___")?;
+    writeln!(out, "{}", events_short)?;
+
+    writeln!(out, "___ Balance changes ___")?;
+    writeln!(
+        out,
+        "{}",
+        summarize_balance_changes(&report.balance_changes)
+    )?;
+
+    Ok(())
+}
+
+/// One `--> type( key: value, ... )` line per event, leaving out `tx`
+/// events.
+pub fn summarize_events(events: &[EventReport]) -> String {
+    let mut events_short: String = "".to_string();
+
+    for event in events {
+        if event.event_type != "tx" {
+            let attributes: Vec<String> = event
+                .attributes
+                .iter()
+                .map(|attribute| match &attribute.label {
+                    Some(label) => format!("{}: {} ({})", attribute.key, attribute.value, label),
+                    None => format!("{}: {}", attribute.key, attribute.value),
+                })
+                .collect();
+
+            events_short +=
+                format!("--> {}( {} )\n", event.event_type, attributes.join(", ")).as_str();
+        }
+    }
+    events_short
+}
+
+/// Nets the coins moved by a tx per address and denom. `coin_spent` and
+/// `coin_received` cover transfers, mints, burns and the fee; nodes that do
+/// not emit them are read from `transfer`, `mint` and `burn` instead. Using
+/// both would count every transfer twice.
+pub fn balance_changes(events: &[EventReport], denoms: &DenomRegistry) -> Vec<BalanceChange> {
+    let coin_events = events
+        .iter()
+        .any(|event| event.event_type == "coin_spent" || event.event_type == "coin_received");
+
+    let mut ledger = Ledger::default();
+    for event in events {
+        match (event.event_type.as_str(), coin_events) {
+            ("coin_spent", true) => ledger.book(event, "spender", -1),
+            ("coin_received", true) => ledger.book(event, "receiver", 1),
+            ("transfer", false) => {
+                ledger.book(event, "sender", -1);
+                ledger.book(event, "recipient", 1);
+            }
+            ("mint", false) => ledger.book(event, "minter", 1),
+            ("burn", false) => ledger.book(event, "burner", -1),
+            _ => {}
+        }
+    }
+
+    for change in &mut ledger.changes {
+        change.display = denoms.display(&change.amount, &change.denom);
+    }
+    ledger.changes
+}
+
+/// Balance changes in the order addresses first moved coins.
+#[derive(Default)]
+struct Ledger {
+    changes: Vec<BalanceChange>,
+}
+
+impl Ledger {
+    /// Books the `amount` of an event against the address in `role`. Merged
+    /// legacy events repeat the pair, so every `role`/`amount` pair counts.
+    fn book(&mut self, event: &EventReport, role: &str, sign: i128) {
+        let mut account: Option<&AttributeReport> = None;
+
+        for attribute in &event.attributes {
+            if attribute.key == role {
+                account = Some(attribute);
+            } else if attribute.key == "amount" {
+                if let Some(account) = account.take() {
+                    for coin in parse_base_coins(&attribute.value).unwrap_or_default() {
+                        let amount: i128 = coin.amount.parse().unwrap_or(0);
+                        self.add(account, &coin.denom, sign * amount);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add(&mut self, account: &AttributeReport, denom: &str, amount: i128) {
+        let existing = self
+            .changes
+            .iter_mut()
+            .find(|change| change.address == account.value && change.denom == denom);
+
+        match existing {
+            Some(change) => {
+                let total: i128 = change.amount.parse().unwrap_or(0) + amount;
+                change.amount = total.to_string();
+            }
+            None => self.changes.push(BalanceChange {
+                address: account.value.clone(),
+                label: account.label.clone(),
+                denom: denom.to_owned(),
+                amount: amount.to_string(),
+                display: None,
+            }),
+        }
+    }
+}
+
+/// One `address  +amount denom` row per non-zero change, labelled addresses
+/// shown by name.
+pub fn summarize_balance_changes(changes: &[BalanceChange]) -> String {
+    let changes: Vec<&BalanceChange> = changes
+        .iter()
+        .filter(|change| change.amount != "0")
+        .collect();
+    if changes.is_empty() {
+        return "none\n".to_owned();
+    }
+
+    let name = |change: &BalanceChange| change.label.clone().unwrap_or(change.address.clone());
+    let width = changes
+        .iter()
+        .map(|change| name(change).len())
+        .max()
+        .unwrap_or(0);
+    let amount = |change: &BalanceChange| {
+        if change.amount.starts_with('-') {
+            change.amount.clone()
+        } else {
+            format!("+{}", change.amount)
+        }
+    };
+    let amount_width = changes
+        .iter()
+        .map(|change| amount(change).len())
+        .max()
+        .unwrap_or(0);
+
+    changes
+        .iter()
+        .map(|change| {
+            let row = format!(
+                "{:width$}  {:>amount_width$} {}",
+                name(change),
+                amount(change),
+                change.denom,
+                width = width,
+                amount_width = amount_width
+            );
+            match &change.display {
+                Some(display) if display.starts_with('-') => format!("{}  ({})\n", row, display),
+                Some(display) => format!("{}  (+{})\n", row, display),
+                None => format!("{}\n", row),
+            }
+        })
+        .collect()
+}
+
+/// Lists the contract calls of a message, in execution order, from its
+/// `execute` and `wasm`/`wasm-*` events. Each `execute` event starts a call;
+/// the first one is the contract the message called and the rest are
+/// submessages, all one level below it since the events do not tell which
+/// call dispatched them. Legacy logs merge events of the same type, so an
+/// event is split again at every `_contract_address` attribute.
+pub fn wasm_calls(events: &[EventReport]) -> Vec<CallReport> {
+    let mut calls: Vec<CallReport> = Vec::new();
+
+    for event in events.iter().filter(|event| event.event_type == "execute") {
+        for chunk in split_by_contract(event) {
+            let depth = if calls.is_empty() { 0 } else { 1 };
+            calls.push(CallReport {
+                contract: chunk[0].value.clone(),
+                label: chunk[0].label.clone(),
+                depth,
+                events: vec![],
+            });
+        }
+    }
+
+    // Events of a contract go to its next call at or after the one the
+    // previous event went to, else to its first call, so repeated calls to
+    // the same contract keep their own events.
+    let mut cursor = 0;
+    for event in events.iter().filter(|event| is_wasm_event(event)) {
+        for chunk in split_by_contract(event) {
+            let contract = &chunk[0].value;
+            let index = (cursor..calls.len())
+                .find(|&i| &calls[i].contract == contract)
+                .or_else(|| calls.iter().position(|call| &call.contract == contract));
+            let index = match index {
+                Some(index) => index,
+                None => {
+                    calls.push(CallReport {
+                        contract: contract.clone(),
+                        label: chunk[0].label.clone(),
+                        depth: if calls.is_empty() { 0 } else { 1 },
+                        events: vec![],
+                    });
+                    calls.len() - 1
+                }
+            };
+            cursor = index;
+
+            let action = chunk
+                .iter()
+                .find(|attribute| attribute.key == "action")
+                .map(|attribute| attribute.value.clone());
+            calls[index].events.push(WasmEventReport {
+                event_type: event.event_type.clone(),
+                action,
+                attributes: chunk[1..]
+                    .iter()
+                    .filter(|attribute| attribute.key != "action")
+                    .cloned()
+                    .collect(),
+            });
+        }
+    }
+
+    calls
+}
+
+fn is_wasm_event(event: &EventReport) -> bool {
+    event.event_type == "wasm" || event.event_type.starts_with("wasm-")
+}
+
+/// Splits attributes into runs that each start at a `_contract_address`,
+/// dropping anything before the first one.
+fn split_by_contract(event: &EventReport) -> Vec<&[AttributeReport]> {
+    let starts: Vec<usize> = event
+        .attributes
+        .iter()
+        .enumerate()
+        .filter(|(_, attribute)| attribute.key == CONTRACT_ADDRESS)
+        .map(|(i, _)| i)
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(event.attributes.len());
+            &event.attributes[start..end]
+        })
+        .collect()
+}
+
+static CONTRACT_ADDRESS: &str = "_contract_address";
+
+/// Renders calls as a list with the submessages indented, each event's
+/// attributes as a table:
+///
+/// ```text
+/// creditManager
+///   wasm: rover/credit-manager/borrow
+///     account_id     10
+///   -> redbank
+///       wasm: borrow
+///         denom          uosmo
+/// ```
+pub fn summarize_calls(calls: &[CallReport]) -> String {
+    let mut summary = String::new();
+
+    for call in calls {
+        let indent = "  ".repeat(call.depth as usize);
+        let name = call.label.as_deref().unwrap_or(&call.contract);
+        if call.depth == 0 {
+            summary += &format!("{}\n", name);
+        } else {
+            summary += &format!("{}-> {}\n", indent, name);
+        }
+
+        let indent = "  ".repeat(call.depth as usize * 2 + 1);
+        for event in &call.events {
+            summary += &format!(
+                "{}{}: {}\n",
+                indent,
+                event.event_type,
+                event.action.as_deref().unwrap_or("-")
+            );
+
+            let width = event
+                .attributes
+                .iter()
+                .map(|attribute| attribute.key.len())
+                .max()
+                .unwrap_or(0);
+            for attribute in &event.attributes {
+                summary += &format!(
+                    "{}  {:width$}  {}",
+                    indent,
+                    attribute.key,
+                    attribute.value,
+                    width = width
+                );
+                if let Some(label) = &attribute.label {
+                    summary += &format!(" ({})", label);
+                }
+                summary += "\n";
+            }
+        }
+    }
+
+    summary
+}
+
+/// Rebuilds per-message logs from events tagged with a `msg_index`
+/// attribute, dropping the tag itself. Untagged events (fees, signatures)
+/// belong to the tx as a whole and stay out of the logs.
+pub fn group_by_msg_index(events: &[EventReport]) -> Vec<LogReport> {
+    let mut logs: Vec<LogReport> = Vec::new();
+
+    for event in events {
+        let msg_index = event
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == MSG_INDEX)
+            .and_then(|attribute| attribute.value.parse::<u32>().ok());
+        let msg_index = match msg_index {
+            Some(msg_index) => msg_index,
+            None => continue,
+        };
+
+        let event = EventReport {
+            event_type: event.event_type.clone(),
+            attributes: event
+                .attributes
+                .iter()
+                .filter(|attribute| attribute.key != MSG_INDEX)
+                .cloned()
+                .collect(),
+        };
+
+        match logs.iter_mut().find(|log| log.msg_index == msg_index) {
+            Some(log) => log.events.push(event),
+            None => logs.push(LogReport {
+                msg_index,
+                events: vec![event],
+                calls: vec![],
+            }),
+        }
+    }
+
+    logs.sort_by_key(|log| log.msg_index);
+    for log in &mut logs {
+        log.calls = wasm_calls(&log.events);
+    }
+    logs
+}
+
+/// Labels attribute values that are addresses in the address book, and coin
+/// amounts in a registered denom with their display amount.
+pub fn label_events(
+    book: &AddressBook,
+    denoms: &DenomRegistry,
+    events: &[Event],
+) -> Vec<EventReport> {
+    events
+        .iter()
+        .map(|event| {
+            let attributes = event
+                .attributes
+                .iter()
+                .enumerate()
+                .map(|(i, attribute)| {
+                    let label = match book.name(&attribute.value) {
+                        Some(name) => Some(name.to_owned()),
+                        None if attribute.key == "amount" => {
+                            denoms.display_coins(&attribute.value).or_else(|| {
+                                let denom = sibling_denom(&event.attributes, i)?;
+                                denoms.display(&attribute.value, denom)
+                            })
+                        }
+                        None => denoms.display_coins(&attribute.value),
+                    };
+
+                    AttributeReport {
+                        key: attribute.key.clone(),
+                        value: attribute.value.clone(),
+                        label,
+                    }
+                })
+                .collect();
+
+            EventReport {
+                event_type: event.event_type.clone(),
+                attributes,
+            }
+        })
+        .collect()
+}
+
+/// The `denom` attribute a bare `amount` at `index` goes with: the closest
+/// one before it, else the first one after it.
+fn sibling_denom(attributes: &[Attribute], index: usize) -> Option<&str> {
+    attributes[..index]
+        .iter()
+        .rev()
+        .chain(&attributes[index + 1..])
+        .find(|attribute| attribute.key == "denom")
+        .map(|attribute| attribute.value.as_str())
+}
+
+/// Base64 decodes every key and value, or returns `None` as soon as one of
+/// them is not base64 encoded printable text.
+pub fn decode_all(events: &[Event]) -> Option<Vec<Event>> {
+    events
+        .iter()
+        .map(|event| {
+            let attributes = event
+                .attributes
+                .iter()
+                .map(|attribute| {
+                    Some(Attribute {
+                        key: decode(&attribute.key)?,
+                        value: decode(&attribute.value)?,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(Event {
+                attributes,
+                event_type: event.event_type.clone(),
+            })
+        })
+        .collect()
+}
+
+fn decode(encoded: &str) -> Option<String> {
+    let decoded = String::from_utf8(STANDARD.decode(encoded).ok()?).ok()?;
+
+    if decoded.chars().any(|c| c.is_control()) {
+        return None;
+    }
+
+    Some(decoded)
+}
@@ -0,0 +1,206 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::denom::DenomRegistry;
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::output::Output;
+use crate::runner::{CmdOutput, Runner};
+use crate::{handle_broadcast, osmosisd, Body, Fund, Message, Wait};
+
+static MSG_EXECUTE_CONTRACT: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+/// One `MsgExecuteContract` of a multi-message transaction.
+#[derive(Debug, Clone)]
+pub struct ExecuteMsg {
+    pub contract: String,
+    pub msg: String,
+    pub funds: Vec<Fund>,
+}
+
+/// An unsigned transaction in the JSON form read by `osmosisd tx sign`.
+#[derive(Serialize, Debug)]
+pub struct UnsignedTx {
+    pub body: Body,
+    pub auth_info: AuthInfo,
+    pub signatures: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AuthInfo {
+    pub signer_infos: Vec<Value>,
+    pub fee: Fee,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Fee {
+    pub amount: Vec<Fund>,
+    pub gas_limit: String,
+    pub payer: String,
+    pub granter: String,
+}
+
+/// Joins funds back into the `10000000uosmo,5uion` form of `--amount`.
+pub fn format_coins(funds: &[Fund]) -> String {
+    funds
+        .iter()
+        .map(|fund| format!("{}{}", fund.amount, fund.denom))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Builds one transaction holding every message, signs it with the
+/// network's signer and broadcasts it. `tx wasm execute` only takes a single
+/// message and `--gas=auto` cannot simulate a tx file, so the gas limit has
+/// to be given.
+pub fn execute_msgs(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    msgs: Vec<ExecuteMsg>,
+    gas_limit: u64,
+    wait: Option<Wait>,
+    out: &mut Output,
+) -> Result<()> {
+    let sender = signer_address(runner, network)?;
+    let input = msgs
+        .iter()
+        .map(|msg| msg.msg.as_str())
+        .collect::<Vec<_>>()
+        .join("\n ");
+
+    let tx = unsigned_tx(network, &sender, msgs, gas_limit)?;
+    let json = serde_json::to_string(&tx).map_err(|e| Error::json("Failed to build tx", e))?;
+
+    let unsigned = temp_path("unsigned");
+    let signed = temp_path("signed");
+    fs::write(&unsigned, json)
+        .map_err(|e| Error::io(format!("Failed to write {}", unsigned.display()), e))?;
+
+    let result = sign_and_broadcast(runner, network, &unsigned, &signed);
+    let _ = fs::remove_file(&unsigned);
+    let _ = fs::remove_file(&signed);
+
+    handle_broadcast(runner, network, denoms, result?, input, wait, out)
+}
+
+/// Wraps the messages into an unsigned tx paying the fee for `gas_limit`.
+pub fn unsigned_tx(
+    network: &Network,
+    sender: &str,
+    msgs: Vec<ExecuteMsg>,
+    gas_limit: u64,
+) -> Result<UnsignedTx> {
+    let messages = msgs
+        .into_iter()
+        .map(|msg| {
+            Ok(Message {
+                type_: MSG_EXECUTE_CONTRACT.to_owned(),
+                sender: sender.to_owned(),
+                contract: msg.contract,
+                msg: serde_json::from_str(&msg.msg)
+                    .map_err(|e| Error::json("Invalid JSON message", e))?,
+                funds: msg.funds,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let fee = (gas_limit as f64 * network.gas_price).ceil() as u128;
+
+    Ok(UnsignedTx {
+        body: Body {
+            messages,
+            memo: "".to_owned(),
+            timeout_height: "0".to_owned(),
+            extension_options: vec![],
+            non_critical_extension_options: vec![],
+        },
+        auth_info: AuthInfo {
+            signer_infos: vec![],
+            fee: Fee {
+                amount: vec![Fund {
+                    denom: network.fee_denom.clone(),
+                    amount: fee.to_string(),
+                }],
+                gas_limit: gas_limit.to_string(),
+                payer: "".to_owned(),
+                granter: "".to_owned(),
+            },
+        },
+        signatures: vec![],
+    })
+}
+
+fn sign_and_broadcast(
+    runner: &dyn Runner,
+    network: &Network,
+    unsigned: &Path,
+    signed: &Path,
+) -> Result<CmdOutput> {
+    let output = osmosisd(
+        runner,
+        &[
+            "tx".to_owned(),
+            "sign".to_owned(),
+            unsigned.display().to_string(),
+            format!("--from={}", network.signer),
+            format!("--keyring-backend={}", network.keyring_backend),
+            format!("--chain-id={}", network.chain_id),
+            format!("--node={}", network.rpc),
+            format!("--output-document={}", signed.display()),
+        ],
+    )?;
+
+    if !output.success() {
+        return Err(Error::Osmosisd {
+            status: output.status,
+            stderr: output.stderr,
+        });
+    }
+
+    osmosisd(
+        runner,
+        &[
+            "tx".to_owned(),
+            "broadcast".to_owned(),
+            signed.display().to_string(),
+            "--output=json".to_owned(),
+            format!("--node={}", network.rpc),
+        ],
+    )
+}
+
+/// Looks up the bech32 address of the network's signer key.
+pub fn signer_address(runner: &dyn Runner, network: &Network) -> Result<String> {
+    let output = osmosisd(
+        runner,
+        &[
+            "keys".to_owned(),
+            "show".to_owned(),
+            network.signer.clone(),
+            "-a".to_owned(),
+            format!("--keyring-backend={}", network.keyring_backend),
+        ],
+    )?;
+
+    if !output.success() {
+        return Err(Error::Osmosisd {
+            status: output.status,
+            stderr: output.stderr,
+        });
+    }
+
+    Ok(output.stdout.trim().to_owned())
+}
+
+fn temp_path(kind: &str) -> PathBuf {
+    env::temp_dir().join(format!(
+        "osmosis-cli-wrapper-{}-{}.json",
+        process::id(),
+        kind
+    ))
+}
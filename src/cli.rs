@@ -0,0 +1,399 @@
+use clap::{ArgGroup, ArgMatches, Args, Parser, Subcommand};
+use clap_complete::Shell;
+
+use std::time::Duration;
+
+use crate::actions::{action_path, MessageKind, MESSAGES};
+use crate::credit::Action;
+use crate::denom::DenomRegistry;
+use crate::error::{Error, Result};
+use crate::output::OutputFormat;
+use crate::template::{parse_var, Vars};
+use crate::{MessageSource, Wait};
+
+/// Runs Mars contract messages through osmosisd.
+#[derive(Parser, Debug)]
+#[clap(name = "osmosis-cli-wrapper", version)]
+pub struct Cli {
+    /// Network profile from config/networks.toml
+    #[clap(long, global = true, env = "OSMOSIS_NETWORK", value_parser)]
+    pub network: Option<String>,
+
+    /// Output format
+    #[clap(long, global = true, value_enum, default_value = "text")]
+    pub output: OutputFormat,
+
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Sign and broadcast contract messages, batching repeated --contract
+    Execute(ExecuteArgs),
+    /// Query a contract
+    Query(QueryArgs),
+    /// Deposit, withdraw, borrow and lend through a creditManager account
+    CreditAccount(CreditAccountArgs),
+    /// Show the positions, debts and health of a credit account
+    Account {
+        /// Id of the credit account
+        #[clap(value_parser)]
+        id: String,
+    },
+    /// List the credit accounts owned by an address
+    Accounts {
+        /// Owner as a name or address; defaults to the network's signer
+        #[clap(long, value_parser)]
+        owner: Option<String>,
+
+        /// Add a one line summary of each account's position
+        #[clap(long)]
+        positions: bool,
+
+        /// Accounts fetched per accountNft query
+        #[clap(long, default_value = "10", value_parser = clap::value_parser!(u32).range(1..))]
+        page_size: u32,
+    },
+    /// Show the totals, rates and TVL of every redbank market
+    Markets {
+        /// Markets fetched per redbank query
+        #[clap(long, default_value = "10", value_parser = clap::value_parser!(u32).range(1..))]
+        page_size: u32,
+    },
+    /// Show the messages, events and balance changes of a tx
+    Analyze(AnalyzeArgs),
+    /// List the execute and query actions that have a message file
+    List {
+        /// Only list the actions of this contract
+        #[clap(long, value_parser)]
+        contract: Option<String>,
+    },
+    /// List, look up or add address book entries
+    Addr {
+        #[clap(subcommand)]
+        action: Option<AddrCommand>,
+    },
+    /// Print a shell completion script
+    Completions {
+        #[clap(value_parser)]
+        shell: Shell,
+    },
+}
+
+#[derive(Args, Debug)]
+#[clap(group(
+    ArgGroup::new("message")
+        .required(true)
+        .multiple(true)
+        .args(&["json", "msg", "action"])
+))]
+pub struct ExecuteArgs {
+    /// Name of the contract to execute; repeat to batch messages
+    #[clap(long, required = true, multiple_occurrences = true, value_parser)]
+    pub contract: Vec<String>,
+
+    /// JSON file that contains the message, or - to read it from stdin
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub json: Vec<String>,
+
+    /// The message as inline JSON
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub msg: Vec<String>,
+
+    /// Action whose message is json/<contract>/execute-<action>.json
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub action: Vec<String>,
+
+    /// Amount to send with the preceding --contract, e.g. 1000000uosmo or 1.5OSMO;
+    /// creditManager deposits fill it in
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub amount: Vec<String>,
+
+    #[clap(flatten)]
+    pub vars: VarArgs,
+
+    /// Gas limit of several messages, which it is required for; a single
+    /// message has its gas estimated
+    #[clap(long, value_parser)]
+    pub gas: Option<u64>,
+
+    /// Estimate gas and fee without signing or broadcasting
+    #[clap(long, conflicts_with = "wait")]
+    pub simulate: bool,
+
+    #[clap(flatten)]
+    pub wait: WaitArgs,
+}
+
+#[derive(Args, Debug)]
+#[clap(group(
+    ArgGroup::new("actions")
+        .required(true)
+        .multiple(true)
+        .args(&["deposit", "withdraw", "borrow", "lend"])
+))]
+pub struct CreditAccountArgs {
+    /// Id of the credit account to update
+    #[clap(long, value_parser)]
+    pub account: String,
+
+    /// Deposit a coin, e.g. 10uosmo or 1.5OSMO; it is sent along with the tx
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub deposit: Vec<String>,
+
+    /// Withdraw a coin from the account to the signer
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub withdraw: Vec<String>,
+
+    /// Borrow a coin from the red bank into the account
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub borrow: Vec<String>,
+
+    /// Lend a coin of the account to the red bank
+    #[clap(long, multiple_occurrences = true, value_parser)]
+    pub lend: Vec<String>,
+
+    /// Estimate gas and fee without signing or broadcasting
+    #[clap(long, conflicts_with = "wait")]
+    pub simulate: bool,
+
+    #[clap(flatten)]
+    pub wait: WaitArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct WaitArgs {
+    /// Wait for the tx to be included and analyze it
+    #[clap(long)]
+    pub wait: bool,
+
+    /// How long --wait polls before giving up, in seconds
+    #[clap(long, default_value = "60", requires = "wait", value_parser)]
+    pub timeout: u64,
+
+    /// Delay between --wait polls, in seconds
+    #[clap(long, default_value = "2", requires = "wait", value_parser)]
+    pub poll_interval: u64,
+}
+
+impl WaitArgs {
+    pub fn wait(&self) -> Option<Wait> {
+        if !self.wait {
+            return None;
+        }
+
+        Some(Wait {
+            timeout: Duration::from_secs(self.timeout),
+            interval: Duration::from_secs(self.poll_interval),
+        })
+    }
+}
+
+#[derive(Args, Debug)]
+#[clap(group(ArgGroup::new("message").required(true).args(&["json", "msg", "action"])))]
+pub struct QueryArgs {
+    /// Name of the contract to query
+    #[clap(long, value_parser)]
+    pub contract: String,
+
+    /// JSON file that contains the message, or - to read it from stdin
+    #[clap(long, value_parser)]
+    pub json: Option<String>,
+
+    /// The message as inline JSON
+    #[clap(long, value_parser)]
+    pub msg: Option<String>,
+
+    /// Action whose message is json/<contract>/query-<action>.json
+    #[clap(long, value_parser)]
+    pub action: Option<String>,
+
+    #[clap(flatten)]
+    pub vars: VarArgs,
+}
+
+impl QueryArgs {
+    pub fn source(&self) -> Result<MessageSource> {
+        match (&self.json, &self.msg, &self.action) {
+            (Some(path), _, _) => Ok(message_source(path)),
+            (_, Some(msg), _) => Ok(MessageSource::Inline(msg.clone())),
+            (_, _, Some(action)) => Ok(MessageSource::File(action_path(
+                MESSAGES,
+                &self.contract,
+                MessageKind::Query,
+                action,
+            )?)),
+            _ => unreachable!("clap requires --json, --msg or --action"),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct AnalyzeArgs {
+    /// Hash of the tx
+    #[clap(value_parser)]
+    pub tx: String,
+
+    /// Show event attributes exactly as the node returned them
+    #[clap(long)]
+    pub raw_events: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AddrCommand {
+    /// List every entry (the default)
+    List,
+    /// Show the entry for a name or address
+    Lookup {
+        #[clap(value_parser)]
+        name_or_address: String,
+    },
+    /// Name a wallet address
+    Add {
+        #[clap(value_parser)]
+        name: String,
+        #[clap(value_parser)]
+        address: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct VarArgs {
+    /// Template variable for the message, as key=value
+    #[clap(long = "var", multiple_occurrences = true, value_parser)]
+    pub pairs: Vec<String>,
+}
+
+impl VarArgs {
+    pub fn vars(&self) -> Result<Vars> {
+        let mut vars = Vars::new();
+        for pair in &self.pairs {
+            let (key, value) = parse_var(pair)?;
+            vars.insert(key, value);
+        }
+        Ok(vars)
+    }
+}
+
+/// A `--contract` with the message and funds that go with it.
+#[derive(Debug)]
+pub struct MessageArgs {
+    pub contract: String,
+    pub source: MessageSource,
+    pub amount: Option<String>,
+}
+
+/// Pairs every `--json`/`--msg`/`--action` and `--amount` of `execute` with
+/// the `--contract` before it, or the first one when it comes before them
+/// all. Each `--contract` needs exactly one message and takes at most one
+/// amount. Derive parsing loses the order of the flags, so this reads the
+/// positions from `matches`.
+pub fn message_args(matches: &ArgMatches) -> Result<Vec<MessageArgs>> {
+    let contracts = indexed(matches, "contract");
+    if contracts.is_empty() {
+        return Err(Error::config("Need a --contract"));
+    }
+    let owner = |i: usize| contracts.iter().rposition(|(c, _)| *c < i).unwrap_or(0);
+
+    // Actions can only be resolved once they are paired with their contract.
+    let mut sources: Vec<Option<std::result::Result<MessageSource, &str>>> =
+        contracts.iter().map(|_| None).collect();
+    let given = indexed(matches, "json")
+        .into_iter()
+        .map(|(i, path)| (i, Ok(message_source(path))))
+        .chain(
+            indexed(matches, "msg")
+                .into_iter()
+                .map(|(i, msg)| (i, Ok(MessageSource::Inline(msg.to_owned())))),
+        )
+        .chain(
+            indexed(matches, "action")
+                .into_iter()
+                .map(|(i, action)| (i, Err(action))),
+        );
+    for (i, source) in given {
+        let owner = owner(i);
+        if sources[owner].is_some() {
+            return Err(Error::config(format!(
+                "--contract {} has more than one --json, --msg or --action",
+                contracts[owner].1
+            )));
+        }
+        sources[owner] = Some(source);
+    }
+
+    let mut messages: Vec<MessageArgs> = contracts
+        .iter()
+        .zip(sources)
+        .map(|((_, contract), source)| {
+            let source = match source {
+                Some(Ok(source)) => source,
+                Some(Err(action)) => MessageSource::File(action_path(
+                    MESSAGES,
+                    contract,
+                    MessageKind::Execute,
+                    action,
+                )?),
+                None => {
+                    return Err(Error::config(format!(
+                        "--contract {} needs a --json, --msg or --action",
+                        contract
+                    )))
+                }
+            };
+
+            Ok(MessageArgs {
+                contract: contract.to_string(),
+                source,
+                amount: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for (i, amount) in indexed(matches, "amount") {
+        let owner = owner(i);
+        if messages[owner].amount.is_some() {
+            return Err(Error::config(format!(
+                "--contract {} has more than one --amount",
+                messages[owner].contract
+            )));
+        }
+        messages[owner].amount = Some(amount.to_owned());
+    }
+
+    Ok(messages)
+}
+
+/// The `--deposit`/`--withdraw`/`--borrow`/`--lend` flags of
+/// `credit-account` as actions, in the order they were given.
+pub fn credit_actions(matches: &ArgMatches, denoms: &DenomRegistry) -> Result<Vec<Action>> {
+    let mut coins: Vec<(usize, &str, &str)> = ["deposit", "withdraw", "borrow", "lend"]
+        .iter()
+        .flat_map(|kind| {
+            indexed(matches, kind)
+                .into_iter()
+                .map(move |(i, coin)| (i, *kind, coin))
+        })
+        .collect();
+    coins.sort_by_key(|(i, _, _)| *i);
+
+    coins
+        .into_iter()
+        .map(|(_, kind, coin)| Action::parse(kind, coin, denoms))
+        .collect()
+}
+
+fn message_source(path: &str) -> MessageSource {
+    match path {
+        "-" => MessageSource::Stdin,
+        path => MessageSource::File(path.to_owned()),
+    }
+}
+
+fn indexed<'a>(matches: &'a ArgMatches, name: &str) -> Vec<(usize, &'a str)> {
+    match (matches.indices_of(name), matches.get_many::<String>(name)) {
+        (Some(indices), Some(values)) => indices.zip(values.map(|v| v.as_str())).collect(),
+        _ => vec![],
+    }
+}
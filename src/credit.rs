@@ -0,0 +1,252 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+
+use crate::batch::format_coins;
+use crate::denom::DenomRegistry;
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::output::Output;
+use crate::runner::Runner;
+use crate::{amount_flag, execute_tx, get_contract_address, simulate_tx, Fund, Wait};
+
+/// Contract names, as in the contracts file, whose deposits take funds.
+pub static CREDIT_MANAGER: &str = "creditManager";
+pub static RED_BANK: &str = "redbank";
+
+/// One step of a creditManager `update_credit_account`, serialized the way
+/// the contract expects it, e.g. `{"deposit": {"denom": ..., "amount": ...}}`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Deposit(Fund),
+    Withdraw(Fund),
+    Borrow(Fund),
+    Lend(Fund),
+}
+
+impl Action {
+    /// Parses the coin of a `--deposit`, `--withdraw`, `--borrow` or
+    /// `--lend` flag, named by `kind`.
+    pub fn parse(kind: &str, coin: &str, denoms: &DenomRegistry) -> Result<Self> {
+        if coin.contains(',') {
+            return Err(Error::config(format!(
+                "--{} takes a single coin, got {}",
+                kind, coin
+            )));
+        }
+
+        let fund = denoms.parse_coin(coin)?;
+        if fund.amount == "0" {
+            return Err(Error::config(format!(
+                "--{} needs a positive amount, got {}",
+                kind, coin
+            )));
+        }
+
+        match kind {
+            "deposit" => Ok(Action::Deposit(fund)),
+            "withdraw" => Ok(Action::Withdraw(fund)),
+            "borrow" => Ok(Action::Borrow(fund)),
+            "lend" => Ok(Action::Lend(fund)),
+            _ => Err(Error::config(format!(
+                "Unknown credit account action {}",
+                kind
+            ))),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::Deposit(_) => "deposit",
+            Action::Withdraw(_) => "withdraw",
+            Action::Borrow(_) => "borrow",
+            Action::Lend(_) => "lend",
+        }
+    }
+
+    pub fn fund(&self) -> &Fund {
+        match self {
+            Action::Deposit(fund)
+            | Action::Withdraw(fund)
+            | Action::Borrow(fund)
+            | Action::Lend(fund) => fund,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditManagerMsg {
+    UpdateCreditAccount {
+        account_id: String,
+        actions: Vec<Action>,
+    },
+}
+
+impl CreditManagerMsg {
+    /// Builds an `update_credit_account` message. The account id has to be
+    /// a number and deposits have to come before every other action, since
+    /// the contract runs them in order and the rest may rely on the
+    /// deposited collateral.
+    pub fn update_credit_account(account_id: &str, actions: Vec<Action>) -> Result<Self> {
+        if account_id.is_empty() || !account_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::config(format!(
+                "Invalid credit account id: {}",
+                account_id
+            )));
+        }
+        if actions.is_empty() {
+            return Err(Error::config("A credit account update needs an action"));
+        }
+
+        if let Some(first) = actions
+            .iter()
+            .position(|action| !matches!(action, Action::Deposit(_)))
+        {
+            if let Some(late) = actions[first..]
+                .iter()
+                .find(|action| matches!(action, Action::Deposit(_)))
+            {
+                let fund = late.fund();
+                return Err(Error::config(format!(
+                    "--deposit {}{} comes after a --{}; deposits go first",
+                    fund.amount,
+                    fund.denom,
+                    actions[first].kind()
+                )));
+            }
+        }
+
+        Ok(CreditManagerMsg::UpdateCreditAccount {
+            account_id: account_id.to_owned(),
+            actions,
+        })
+    }
+
+    /// The funds the tx has to send along: every deposit, summed per denom
+    /// and sorted by denom as the chain requires.
+    pub fn funds(&self) -> Result<Vec<Fund>> {
+        let CreditManagerMsg::UpdateCreditAccount { actions, .. } = self;
+
+        sum_funds(actions.iter().filter_map(|action| match action {
+            Action::Deposit(fund) => Some(fund),
+            _ => None,
+        }))
+    }
+}
+
+/// Executes, or with `simulate` only simulates, `msg` on the network's
+/// creditManager with its deposits attached as `--amount`.
+pub fn execute_credit_account(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    msg: &CreditManagerMsg,
+    simulate: bool,
+    wait: Option<Wait>,
+    out: &mut Output,
+) -> Result<()> {
+    let json = serde_json::to_string(msg)
+        .map_err(|e| Error::json("Failed to serialize the credit account message", e))?;
+    let contract_address = get_contract_address(network, CREDIT_MANAGER)?;
+    let amount = amount_flag(&msg.funds()?);
+
+    if simulate {
+        simulate_tx(runner, network, contract_address, json, amount, out)
+    } else {
+        execute_tx(
+            runner,
+            network,
+            denoms,
+            contract_address,
+            json,
+            amount,
+            wait,
+            out,
+        )
+    }
+}
+
+/// The funds to send with an execute message of `contract`. The deposit
+/// actions of a creditManager `update_credit_account` decide them, and an
+/// explicit `--amount` has to agree. A redbank `deposit` takes whatever is
+/// sent, so it needs an explicit `--amount`. Anything else sends `amount`
+/// as given.
+pub fn message_funds(contract: &str, json: &str, amount: Option<Vec<Fund>>) -> Result<Vec<Fund>> {
+    let msg: Value = serde_json::from_str(json)
+        .map_err(|e| Error::json(format!("Failed to parse the {} message", contract), e))?;
+
+    if contract == RED_BANK && msg.get("deposit").is_some() && amount.is_none() {
+        return Err(Error::config(
+            "A redbank deposit deposits the funds sent with it; pass an --amount",
+        ));
+    }
+
+    let deposits = match msg
+        .pointer("/update_credit_account/actions")
+        .and_then(Value::as_array)
+    {
+        Some(actions) if contract == CREDIT_MANAGER => actions
+            .iter()
+            .filter_map(|action| action.get("deposit"))
+            .map(deposit_fund)
+            .collect::<Result<Vec<_>>>()?,
+        _ => vec![],
+    };
+    if deposits.is_empty() {
+        return Ok(amount.unwrap_or_default());
+    }
+
+    let required = sum_funds(&deposits)?;
+    if let Some(amount) = amount {
+        let explicit = sum_funds(&amount)?;
+        if format_coins(&explicit) != format_coins(&required) {
+            return Err(Error::config(format!(
+                "--amount {} does not match the deposits of the {} message: {}",
+                format_coins(&amount),
+                contract,
+                format_coins(&required)
+            )));
+        }
+    }
+
+    Ok(required)
+}
+
+fn deposit_fund(deposit: &Value) -> Result<Fund> {
+    match (
+        deposit.get("denom").and_then(Value::as_str),
+        deposit.get("amount").and_then(Value::as_str),
+    ) {
+        (Some(denom), Some(amount)) => Ok(Fund {
+            denom: denom.to_owned(),
+            amount: amount.to_owned(),
+        }),
+        _ => Err(Error::config(format!("Invalid deposit: {}", deposit))),
+    }
+}
+
+/// Sums funds per denom, sorted by denom as the chain requires.
+fn sum_funds<'a>(funds: impl IntoIterator<Item = &'a Fund>) -> Result<Vec<Fund>> {
+    let mut totals: BTreeMap<&str, u128> = BTreeMap::new();
+    for fund in funds {
+        let amount: u128 = fund
+            .amount
+            .parse()
+            .map_err(|_| Error::config(format!("Invalid amount: {}{}", fund.amount, fund.denom)))?;
+        let total = totals.entry(&fund.denom).or_default();
+        *total = total
+            .checked_add(amount)
+            .ok_or_else(|| Error::config(format!("Funds of {} overflow", fund.denom)))?;
+    }
+
+    Ok(totals
+        .into_iter()
+        .map(|(denom, amount)| Fund {
+            denom: denom.to_owned(),
+            amount: amount.to_string(),
+        })
+        .collect())
+}
@@ -0,0 +1,184 @@
+use serde::Deserialize;
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::Fund;
+
+pub static DENOMS: &str = "config/denoms.json";
+
+/// A token as users write it (`OSMO`) and as the chain stores it (`uosmo`,
+/// `ibc/...`, `factory/...`), `exponent` decimals apart.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Denom {
+    pub symbol: String,
+    pub denom: String,
+    pub exponent: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DenomRegistry {
+    denoms: Vec<Denom>,
+}
+
+impl DenomRegistry {
+    pub fn new(denoms: Vec<Denom>) -> Self {
+        DenomRegistry { denoms }
+    }
+
+    /// Reads a JSON array of denoms. A missing file gives an empty registry,
+    /// which leaves every amount in base units.
+    pub fn load(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Ok(DenomRegistry::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| Error::io(format!("Failed to read denoms file {}", path), e))?;
+        let denoms: Vec<Denom> = serde_json::from_str(&contents)
+            .map_err(|e| Error::json(format!("Failed to parse denoms file {}", path), e))?;
+
+        Ok(DenomRegistry::new(denoms))
+    }
+
+    pub fn by_denom(&self, denom: &str) -> Option<&Denom> {
+        self.denoms.iter().find(|d| d.denom == denom)
+    }
+
+    /// Symbols match case-insensitively, but never shadow a base denom.
+    pub fn by_symbol(&self, symbol: &str) -> Option<&Denom> {
+        if self.by_denom(symbol).is_some() {
+            return None;
+        }
+        self.denoms
+            .iter()
+            .find(|d| d.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Parses a coin list such as `1.5OSMO,5uion` into base units. Base
+    /// denoms take whole amounts only.
+    pub fn parse_coins(&self, coins: &str) -> Result<Vec<Fund>> {
+        coins.split(',').map(|coin| self.parse_coin(coin)).collect()
+    }
+
+    pub fn parse_coin(&self, coin: &str) -> Result<Fund> {
+        let coin = coin.trim();
+        let split = coin
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(coin.len());
+        let (amount, denom) = coin.split_at(split);
+        let denom = denom.trim();
+
+        if amount.is_empty() || denom.is_empty() {
+            return Err(Error::config(format!("Invalid amount: {}", coin)));
+        }
+
+        let (denom, exponent) = match self.by_symbol(denom) {
+            Some(known) => (known.denom.clone(), known.exponent),
+            None => (denom.to_owned(), 0),
+        };
+
+        Ok(Fund {
+            amount: to_base(amount, exponent)
+                .ok_or_else(|| Error::config(format!("Invalid amount: {}", coin)))?,
+            denom,
+        })
+    }
+
+    /// `1.5 OSMO` for `1500000` `uosmo`, or `None` for unknown denoms.
+    pub fn display(&self, amount: &str, denom: &str) -> Option<String> {
+        let known = self.by_denom(denom)?;
+        Some(format!(
+            "{} {}",
+            to_display(amount, known.exponent)?,
+            known.symbol
+        ))
+    }
+
+    /// `display` for a `1500000uosmo,5uion` coin list, skipping unknown
+    /// denoms. `None` when it is not a coin list or none of them is known.
+    pub fn display_coins(&self, coins: &str) -> Option<String> {
+        let displayed: Vec<String> = parse_base_coins(coins)
+            .ok()?
+            .iter()
+            .filter_map(|coin| self.display(&coin.amount, &coin.denom))
+            .collect();
+
+        if displayed.is_empty() {
+            None
+        } else {
+            Some(displayed.join(", "))
+        }
+    }
+}
+
+/// Parses a coin list in base units, such as `10000000uosmo,5uion`, the way
+/// the chain writes it in events.
+pub fn parse_base_coins(coins: &str) -> Result<Vec<Fund>> {
+    coins
+        .split(',')
+        .map(|coin| {
+            let coin = coin.trim();
+            let split = coin
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(coin.len());
+            let (amount, denom) = coin.split_at(split);
+
+            if amount.is_empty() || denom.is_empty() {
+                return Err(Error::config(format!("Invalid amount: {}", coin)));
+            }
+
+            Ok(Fund {
+                denom: denom.to_owned(),
+                amount: amount.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Shifts a decimal amount `exponent` places into a whole number:
+/// `to_base("1.5", 6)` is `1500000`. `None` if it has more decimals than
+/// that.
+pub fn to_base(amount: &str, exponent: u32) -> Option<String> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+
+    if (whole.is_empty() && fraction.is_empty()) || !digits(whole) || !digits(fraction) {
+        return None;
+    }
+    if fraction.len() > exponent as usize {
+        return None;
+    }
+
+    let base = format!("{}{:0<width$}", whole, fraction, width = exponent as usize);
+    let base = base.trim_start_matches('0');
+    Some(if base.is_empty() { "0" } else { base }.to_owned())
+}
+
+/// Shifts a whole, possibly negative, amount back `exponent` places:
+/// `to_display("1500000", 6)` is `1.5`.
+pub fn to_display(amount: &str, exponent: u32) -> Option<String> {
+    let (sign, digits) = match amount.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", amount),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let exponent = exponent as usize;
+    let padded = format!("{:0>width$}", digits, width = exponent + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - exponent);
+    let whole = match whole.trim_start_matches('0') {
+        "" => "0",
+        whole => whole,
+    };
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        Some(format!("{}{}", sign, whole))
+    } else {
+        Some(format!("{}{}.{}", sign, whole, fraction))
+    }
+}
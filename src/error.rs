@@ -0,0 +1,113 @@
+use std::fmt;
+use std::io;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Bad arguments, unknown network or contract, malformed config.
+    Config(String),
+    Io {
+        context: String,
+        source: io::Error,
+    },
+    Json {
+        context: String,
+        source: serde_json::Error,
+    },
+    /// `osmosisd` ran but exited with a non-zero status.
+    Osmosisd {
+        status: i32,
+        stderr: String,
+    },
+    /// The chain accepted the request but the transaction failed.
+    Chain {
+        code: u32,
+        codespace: String,
+        raw_log: String,
+    },
+    /// A broadcast tx did not show up before the wait timed out.
+    Timeout {
+        txhash: String,
+        secs: u64,
+    },
+}
+
+impl Error {
+    pub fn config(message: impl Into<String>) -> Self {
+        Error::Config(message.into())
+    }
+
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn json(context: impl Into<String>, source: serde_json::Error) -> Self {
+        Error::Json {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Process exit code for this error. Codes start at 3, above clap's 2
+    /// for usage errors; a panic exits with 101.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Config(_) => 3,
+            Error::Io { .. } => 4,
+            Error::Json { .. } => 5,
+            Error::Osmosisd { .. } => 6,
+            Error::Chain { .. } => 7,
+            Error::Timeout { .. } => 8,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Config(message) => write!(f, "{}", message),
+            Error::Io { context, source } => write!(f, "{}: {}", context, source),
+            Error::Json { context, source } => write!(f, "{}: {}", context, source),
+            Error::Osmosisd { status, stderr } => {
+                write!(
+                    f,
+                    "osmosisd exited with status {}: {}",
+                    status,
+                    stderr.trim()
+                )
+            }
+            Error::Chain {
+                code,
+                codespace,
+                raw_log,
+            } => write!(
+                f,
+                "transaction failed with code {} ({}): {}",
+                code, codespace, raw_log
+            ),
+            Error::Timeout { txhash, secs } => {
+                write!(f, "tx {} was not included within {} seconds", txhash, secs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::io("Failed to write output", source)
+    }
+}
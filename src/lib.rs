@@ -0,0 +1,602 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub mod account;
+pub mod actions;
+pub mod address;
+pub mod analyze;
+pub mod batch;
+pub mod cli;
+pub mod credit;
+pub mod denom;
+pub mod error;
+pub mod market;
+pub mod network;
+pub mod output;
+pub mod runner;
+pub mod template;
+
+use analyze::{print_report, print_tx_details, tx_report, TxReport};
+use denom::DenomRegistry;
+use error::{Error, Result};
+use network::Network;
+use output::Output;
+use runner::{CmdOutput, Runner};
+use template::Vars;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Attribute {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Event {
+    pub attributes: Vec<Attribute>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Data {
+    pub code: i32,
+    #[serde(default)]
+    pub codespace: String,
+    #[serde(default)]
+    pub data: String,
+    #[serde(default)]
+    pub raw_log: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    pub tx: Tx,
+    /// Empty since SDK 0.50, which tags the top-level events with a
+    /// `msg_index` attribute instead.
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    pub txhash: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Log {
+    #[serde(default)]
+    pub msg_index: u32,
+    #[serde(default)]
+    pub log: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Message {
+    #[serde(rename = "@type")]
+    pub type_: String,
+    pub sender: String,
+    pub contract: String,
+    pub msg: serde_json::Value,
+    pub funds: Vec<Fund>,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Fund {
+    pub denom: String,
+    pub amount: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Tx {
+    #[serde(rename = "@type")]
+    pub type_: String,
+    pub body: Body,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Body {
+    pub messages: Vec<Message>,
+    pub memo: String,
+    pub timeout_height: String,
+    pub extension_options: Vec<serde_json::Value>,
+    pub non_critical_extension_options: Vec<serde_json::Value>,
+}
+
+/// The part of an `osmosisd tx ... --output=json` response needed to tell
+/// whether the transaction made it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastResponse {
+    pub txhash: String,
+    #[serde(default)]
+    pub code: u32,
+    #[serde(default)]
+    pub codespace: String,
+    #[serde(default)]
+    pub raw_log: String,
+    #[serde(default)]
+    pub height: String,
+    #[serde(default)]
+    pub gas_wanted: String,
+    #[serde(default)]
+    pub gas_used: String,
+}
+
+/// What `execute` emits with `--output json|yaml`: the broadcast result,
+/// plus the analyzed tx when it was waited for.
+#[derive(Serialize, Debug)]
+pub struct ExecuteReport {
+    #[serde(flatten)]
+    pub broadcast: BroadcastResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx: Option<TxReport>,
+}
+
+/// What `execute --simulate` found out.
+#[derive(Serialize, Debug)]
+pub struct Simulation {
+    pub gas_estimate: u64,
+    pub fee: Fund,
+}
+
+/// Multiplier applied to osmosisd's gas estimate.
+pub static GAS_ADJUSTMENT: f64 = 1.3;
+
+/// How long `execute --wait` polls for the broadcast tx to be included.
+#[derive(Debug, Clone, Copy)]
+pub struct Wait {
+    pub timeout: Duration,
+    pub interval: Duration,
+}
+
+/// Where a query or execute message is read from.
+#[derive(Debug, Clone)]
+pub enum MessageSource {
+    /// A message file, rendered with its sidecar defaults.
+    File(String),
+    /// `--json -`
+    Stdin,
+    /// `--msg '<json>'`
+    Inline(String),
+}
+
+/// Reads the message from `source`, fills in its placeholders and returns it
+/// as compact JSON ready to be handed to osmosisd.
+pub fn read_message(source: &MessageSource, vars: &Vars, denoms: &DenomRegistry) -> Result<String> {
+    let (raw, origin) = match source {
+        MessageSource::File(path) => (get_json(path, vars, denoms)?, path.as_str()),
+        MessageSource::Stdin => {
+            let mut contents = String::new();
+            io::stdin()
+                .read_to_string(&mut contents)
+                .map_err(|e| Error::io("Failed to read message from stdin", e))?;
+            (render_message(&contents, vars, denoms, "stdin")?, "stdin")
+        }
+        MessageSource::Inline(msg) => (render_message(msg, vars, denoms, "--msg")?, "--msg"),
+    };
+
+    compact_json(&raw).map_err(|e| Error::json(format!("Invalid JSON message from {}", origin), e))
+}
+
+/// Validates `raw` as JSON and re-serializes it without whitespace.
+pub fn compact_json(raw: &str) -> serde_json::Result<String> {
+    serde_json::from_str::<Value>(raw).and_then(|json| serde_json::to_string(&json))
+}
+
+fn render_message(raw: &str, vars: &Vars, denoms: &DenomRegistry, origin: &str) -> Result<String> {
+    template::render(raw, vars, &Vars::new(), denoms)
+        .map_err(|unresolved| unresolved_error(origin, unresolved))
+}
+
+fn unresolved_error(origin: &str, unresolved: Vec<String>) -> Error {
+    Error::config(format!(
+        "Unresolved variables in {}: {} (pass --var name=value or set {}<NAME>)",
+        origin,
+        unresolved.join(", "),
+        template::ENV_PREFIX
+    ))
+}
+
+/// Reads a message file and fills in its `{{placeholders}}`.
+pub fn get_json(json_path: &str, vars: &Vars, denoms: &DenomRegistry) -> Result<String> {
+    let contents = fs::read_to_string(json_path)
+        .map_err(|e| Error::io(format!("Failed to read JSON file {}", json_path), e))?;
+    let defaults = template::load_defaults(json_path)?;
+
+    template::render(&contents, vars, &defaults, denoms)
+        .map_err(|unresolved| unresolved_error(json_path, unresolved))
+}
+
+/// The `--amount` flag of `tx wasm execute`, or nothing without funds.
+pub fn amount_flag(funds: &[Fund]) -> String {
+    if funds.is_empty() {
+        "".to_owned()
+    } else {
+        "--amount=".to_owned() + &batch::format_coins(funds)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_tx(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    contract_address: String,
+    json_str: String,
+    amount: String,
+    wait: Option<Wait>,
+    out: &mut Output,
+) -> Result<()> {
+    let mut args = vec![
+        "tx".to_owned(),
+        "wasm".to_owned(),
+        "execute".to_owned(),
+        contract_address,
+        json_str.clone(),
+        format!("--gas-prices={}", network.gas_prices()),
+        "--gas=auto".to_owned(),
+        format!("--gas-adjustment={}", GAS_ADJUSTMENT),
+        "-y".to_owned(),
+        format!("--keyring-backend={}", network.keyring_backend),
+        "--output=json".to_owned(),
+        format!("--from={}", network.signer),
+        format!("--node={}", network.rpc),
+        format!("--chain-id={}", network.chain_id),
+    ];
+
+    if !amount.is_empty() {
+        args.push(amount);
+    }
+
+    let output = osmosisd(runner, &args)?;
+
+    handle_broadcast(runner, network, denoms, output, json_str, wait, out)
+}
+
+/// Reports the outcome of a broadcast and, with `wait`, follows the tx until
+/// it is included.
+pub(crate) fn handle_broadcast(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    output: CmdOutput,
+    json_str: String,
+    wait: Option<Wait>,
+    out: &mut Output,
+) -> Result<()> {
+    // A failing tx still yields a broadcast response, sometimes along with
+    // a non-zero exit status, so look for one before anything else.
+    let json = match serde_json::from_str::<Value>(&output.stdout) {
+        Ok(json) if json.get("txhash").is_some() => json,
+        _ => return print_result(output, json_str, out),
+    };
+    let broadcast = print_broadcast(json, json_str, out)?;
+
+    let (tx, failure) = match wait {
+        Some(wait) if broadcast.code == 0 => {
+            let data = wait_for_tx(runner, network, &broadcast.txhash, wait)?;
+            let failure = (data.code != 0).then(|| Error::Chain {
+                code: data.code as u32,
+                codespace: data.codespace.clone(),
+                raw_log: data.raw_log.clone(),
+            });
+            (Some(tx_report(network, denoms, data, false)?), failure)
+        }
+        _ => (None, None),
+    };
+
+    if out.is_text() {
+        if let Some(tx) = &tx {
+            print_report(tx, out)?;
+        }
+    } else {
+        out.emit(&ExecuteReport {
+            broadcast: broadcast.clone(),
+            tx,
+        })?;
+    }
+
+    if broadcast.code != 0 {
+        return Err(Error::Chain {
+            code: broadcast.code,
+            codespace: broadcast.codespace,
+            raw_log: broadcast.raw_log,
+        });
+    }
+    if let Some(error) = failure {
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Runs the execute message through `--dry-run`, which estimates gas
+/// without signing or broadcasting, and reports the resulting fee.
+pub fn simulate_tx(
+    runner: &dyn Runner,
+    network: &Network,
+    contract_address: String,
+    json_str: String,
+    amount: String,
+    out: &mut Output,
+) -> Result<()> {
+    // Simulation skips the keyring, so `--from` has to be an address rather
+    // than a key name.
+    let from = batch::signer_address(runner, network)?;
+    let mut args = vec![
+        "tx".to_owned(),
+        "wasm".to_owned(),
+        "execute".to_owned(),
+        contract_address,
+        json_str.clone(),
+        "--gas=auto".to_owned(),
+        format!("--gas-adjustment={}", GAS_ADJUSTMENT),
+        "--dry-run".to_owned(),
+        format!("--keyring-backend={}", network.keyring_backend),
+        format!("--from={}", from),
+        format!("--node={}", network.rpc),
+        format!("--chain-id={}", network.chain_id),
+    ];
+
+    if !amount.is_empty() {
+        args.push(amount);
+    }
+
+    let output = osmosisd(runner, &args)?;
+
+    if out.is_text() {
+        writeln!(out, "Input: \n {}\n", json_str)?;
+    }
+
+    // A contract error surfaces as a failed simulation.
+    if !output.success() {
+        return Err(Error::Osmosisd {
+            status: output.status,
+            stderr: output.stderr,
+        });
+    }
+
+    // osmosisd prints the adjusted estimate as `gas estimate: <n>` on stderr.
+    let gas: u64 = output
+        .stderr
+        .lines()
+        .chain(output.stdout.lines())
+        .find_map(|line| line.trim().strip_prefix("gas estimate: "))
+        .and_then(|gas| gas.trim().parse().ok())
+        .ok_or_else(|| Error::Osmosisd {
+            status: output.status,
+            stderr: format!("no gas estimate in output: {}", output.stderr),
+        })?;
+    let fee = (gas as f64 * network.gas_price).ceil() as u128;
+
+    if !out.is_text() {
+        return out.emit(&Simulation {
+            gas_estimate: gas,
+            fee: Fund {
+                denom: network.fee_denom.clone(),
+                amount: fee.to_string(),
+            },
+        });
+    }
+
+    writeln!(out, "___ Simulation ___")?;
+    writeln!(out, "gas estimate: {}", gas)?;
+    writeln!(
+        out,
+        "fee: {}{} (at {})",
+        fee,
+        network.fee_denom,
+        network.gas_prices()
+    )?;
+
+    Ok(())
+}
+
+pub fn query_contract(
+    runner: &dyn Runner,
+    network: &Network,
+    contract_name: String,
+    query_json: String,
+    out: &mut Output,
+) -> Result<()> {
+    let output = osmosisd(
+        runner,
+        &[
+            "query".to_owned(),
+            "wasm".to_owned(),
+            "contract-state".to_owned(),
+            "smart".to_owned(),
+            contract_name,
+            query_json.clone(),
+            "--output=json".to_owned(),
+            format!("--node={}", network.rpc),
+        ],
+    )?;
+
+    if !out.is_text() && output.success() {
+        // Just the contract's response, without osmosisd's `data` wrapper.
+        let json: Value = serde_json::from_str(&output.stdout)
+            .map_err(|e| Error::json("Failed to parse query response", e))?;
+        return out.emit(json.get("data").unwrap_or(&json));
+    }
+
+    print_result(output, query_json, out)
+}
+
+/// Runs a smart query and parses the contract's response, for commands that
+/// work with query results rather than print them.
+pub fn smart_query<T: DeserializeOwned>(
+    runner: &dyn Runner,
+    network: &Network,
+    contract_address: &str,
+    query: &Value,
+) -> Result<T> {
+    let output = osmosisd(
+        runner,
+        &[
+            "query".to_owned(),
+            "wasm".to_owned(),
+            "contract-state".to_owned(),
+            "smart".to_owned(),
+            contract_address.to_owned(),
+            query.to_string(),
+            "--output=json".to_owned(),
+            format!("--node={}", network.rpc),
+        ],
+    )?;
+
+    if !output.success() {
+        return Err(Error::Osmosisd {
+            status: output.status,
+            stderr: output.stderr,
+        });
+    }
+
+    let json: Value = serde_json::from_str(&output.stdout)
+        .map_err(|e| Error::json("Failed to parse query response", e))?;
+    serde_json::from_value(json.get("data").cloned().unwrap_or(json))
+        .map_err(|e| Error::json(format!("Unexpected response to {}", query), e))
+}
+
+pub fn get_tx_data(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    tx_hash: &str,
+    raw_events: bool,
+    out: &mut Output,
+) -> Result<()> {
+    let output = query_tx(runner, network, tx_hash)?;
+
+    if !output.success() {
+        return Err(Error::Osmosisd {
+            status: output.status,
+            stderr: output.stderr,
+        });
+    }
+
+    print_tx_details(
+        network,
+        denoms,
+        parse_tx(tx_hash, &output)?,
+        raw_events,
+        out,
+    )
+}
+
+/// Polls for `tx_hash` until a node has it indexed or `wait.timeout` passes.
+pub fn wait_for_tx(
+    runner: &dyn Runner,
+    network: &Network,
+    tx_hash: &str,
+    wait: Wait,
+) -> Result<Data> {
+    let started = Instant::now();
+
+    loop {
+        let output = query_tx(runner, network, tx_hash)?;
+
+        if output.success() {
+            return parse_tx(tx_hash, &output);
+        }
+        if !output.stderr.contains("not found") {
+            return Err(Error::Osmosisd {
+                status: output.status,
+                stderr: output.stderr,
+            });
+        }
+        if started.elapsed() >= wait.timeout {
+            return Err(Error::Timeout {
+                txhash: tx_hash.to_owned(),
+                secs: wait.timeout.as_secs(),
+            });
+        }
+
+        thread::sleep(wait.interval);
+    }
+}
+
+fn query_tx(runner: &dyn Runner, network: &Network, tx_hash: &str) -> Result<CmdOutput> {
+    osmosisd(
+        runner,
+        &[
+            "query".to_owned(),
+            "tx".to_owned(),
+            tx_hash.to_owned(),
+            "--output=json".to_owned(),
+            format!("--node={}", network.rpc),
+        ],
+    )
+}
+
+fn parse_tx(tx_hash: &str, output: &CmdOutput) -> Result<Data> {
+    serde_json::from_str(&output.stdout)
+        .map_err(|e| Error::json(format!("Failed to parse tx {}", tx_hash), e))
+}
+
+pub(crate) fn osmosisd(runner: &dyn Runner, args: &[String]) -> Result<CmdOutput> {
+    runner
+        .run(args)
+        .map_err(|e| Error::io("Failed to run osmosisd", e))
+}
+
+/// Parses a broadcast response, printing it in text mode.
+fn print_broadcast(json: Value, json_str: String, out: &mut Output) -> Result<BroadcastResponse> {
+    let pretty = serde_json::to_string_pretty(&json)
+        .map_err(|e| Error::json("Failed to format output", e))?;
+    let response: BroadcastResponse = serde_json::from_value(json)
+        .map_err(|e| Error::json("Failed to parse broadcast response", e))?;
+
+    if !out.is_text() {
+        return Ok(response);
+    }
+
+    writeln!(out, "Input: \n {}\n", json_str)?;
+    writeln!(out, "Output:\n{}", pretty)?;
+
+    if response.code != 0 {
+        writeln!(out, "___ Tx failed ___")?;
+        writeln!(out, "txhash: {}", response.txhash)?;
+        writeln!(out, "codespace: {}", response.codespace)?;
+        writeln!(out, "code: {}", response.code)?;
+        writeln!(out, "raw_log: {}", response.raw_log)?;
+    }
+
+    Ok(response)
+}
+
+fn print_result(output: CmdOutput, json_str: String, out: &mut Output) -> Result<()> {
+    if out.is_text() {
+        writeln!(out, "Input: \n {}\n", json_str)?;
+    }
+
+    if !output.success() {
+        return Err(Error::Osmosisd {
+            status: output.status,
+            stderr: output.stderr,
+        });
+    }
+
+    if let Ok(json) = serde_json::from_str::<Value>(&output.stdout) {
+        if !out.is_text() {
+            return out.emit(&json);
+        }
+        let pretty = serde_json::to_string_pretty(&json)
+            .map_err(|e| Error::json("Failed to format output", e))?;
+        writeln!(out, "Output:\n{}", pretty)?;
+    } else if !out.is_text() {
+        out.emit(&output.stdout)?;
+    } else {
+        writeln!(out, "Output:\n{}", output.stdout)?;
+    }
+
+    Ok(())
+}
+
+/// Address of the contract called `contract_name` in the network's address
+/// book.
+pub fn get_contract_address(network: &Network, contract_name: &str) -> Result<String> {
+    Ok(network
+        .address_book()?
+        .contract_address(contract_name)?
+        .to_owned())
+}
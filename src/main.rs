@@ -1,334 +1,219 @@
-use clap::{arg, App, Arg};
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use serde_json::{json, to_string_pretty, Result};
-
-use std::fs;
-use std::fs::File;
-use std::io::Read;
-use std::process::{Command, Output};
-use std::str;
-
-use base64;
-
-static NODE: &str = "https://rpc.osmotest5.osmosis.zone:443";
-static TESTNET: &str = "osmo-test-5";
-static WALLET: &str = "wallet";
-static CONTRACTS: &str = "config/rover-osmosis5-contracts.json";
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct Attribute {
-    key: String,
-    value: String,
-}
-
-#[derive(Deserialize, Debug, Clone)]
-struct Event {
-    attributes: Vec<Attribute>,
-    #[serde(rename = "type")]
-    event_type: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct Data {
-    code: i32,
-    codespace: String,
-    data: String,
-    events: Vec<Event>,
-    tx: Tx,
-    logs: Vec<Log>,
-    txhash: String,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct Log {
-    msg_index: u32,
-    log: String,
-    events: Vec<Event>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Message {
-    #[serde(rename = "@type")]
-    type_: String,
-    sender: String,
-    contract: String,
-    msg: serde_json::Value,
-    funds: Vec<Fund>,
-}
-#[derive(Serialize, Deserialize, Debug)]
-struct Fund {
-    denom: String,
-    amount: String,
-}
-#[derive(Serialize, Deserialize, Debug)]
-struct Tx {
-    #[serde(rename = "@type")]
-    type_: String,
-    body: Body,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Body {
-    messages: Vec<Message>,
-    memo: String,
-    timeout_height: String,
-    extension_options: Vec<serde_json::Value>,
-    non_critical_extension_options: Vec<serde_json::Value>,
-}
+use clap::{CommandFactory, FromArgMatches};
+
+use std::io;
+use std::process;
+
+use osmosis_cli_wrapper::account::{
+    account_report, accounts_report, print_account, print_accounts,
+};
+use osmosis_cli_wrapper::actions::{list_actions, print_actions, MESSAGES};
+use osmosis_cli_wrapper::address::{add_wallet, print_entries};
+use osmosis_cli_wrapper::batch::{execute_msgs, ExecuteMsg};
+use osmosis_cli_wrapper::cli::{
+    credit_actions, message_args, AddrCommand, Cli, Command, CreditAccountArgs, ExecuteArgs,
+};
+use osmosis_cli_wrapper::credit::{execute_credit_account, message_funds, CreditManagerMsg};
+use osmosis_cli_wrapper::denom::DenomRegistry;
+use osmosis_cli_wrapper::error::{Error, Result};
+use osmosis_cli_wrapper::market::{markets_report, print_markets};
+use osmosis_cli_wrapper::network::{load_network, Network, NETWORKS};
+use osmosis_cli_wrapper::output::Output;
+use osmosis_cli_wrapper::runner::{Osmosisd, Runner};
+use osmosis_cli_wrapper::{
+    amount_flag, execute_tx, get_contract_address, get_tx_data, query_contract, read_message,
+    simulate_tx,
+};
 
 fn main() {
-    let matches = App::new("Osmosis CLI wrapper")
-        .arg(
-            arg!(--cmd <command>)
-                .required(true)
-                .takes_value(true)
-                .help("command to execute: execute|query|analyze"),
-        )
-        .arg(
-            arg!(--contract <contract_name>)
-                .required(false)
-                .takes_value(true)
-                .help("name of the contract to execute or query"),
-        )
-        .arg(
-            arg!(--json <file>)
-                .required(false)
-                .takes_value(true)
-                .help("json file that contains the message"),
-        )
-        .arg(
-            arg!(--amount <number_denom>)
-                .required(false)
-                .takes_value(true)
-                .help("amount to send with the tx"),
-        )
-        .arg(
-            arg!(--tx <hash>)
-                .required(false)
-                .takes_value(true)
-                .help("tx hash"),
-        )
-        .get_matches();
-
-    let cmd = matches.value_of("cmd").unwrap();
-    let contract_name = matches.value_of("contract").unwrap_or("");
-    let json_path = matches.value_of("json").unwrap_or("");
-    let tx_hash = matches.value_of("tx").unwrap_or("");
-    let amount = matches
-        .value_of("amount")
-        .map_or("".to_owned(), |a| "--amount=".to_owned() + a);
-
-    match cmd {
-        "execute" => execute_tx(
-            get_contract_address(contract_name),
-            get_json(json_path),
-            amount,
-        ),
-        "query" => query_contract(get_contract_address(contract_name), get_json(json_path)),
-        "analyze" => {
-            if tx_hash.is_empty() {
-                println!("Need a tx hash to get events");
-            } else {
-                get_tx_data(tx_hash);
-            }
-        }
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-        _ => println!("Cmd should be either query, execute or analyze"),
+    if let Err(error) = run(cli, &matches) {
+        eprintln!("Error: {}", error);
+        process::exit(error.exit_code());
     }
 }
 
-fn get_json(json_path: &str) -> String {
-    fs::read_to_string(json_path).expect("Failed to read JSON file")
-}
-
-fn execute_tx(contract_address: String, json_str: String, amount: String) {
-    let mut cmd = Command::new("osmosisd");
-
-    cmd.arg("tx")
-        .arg("wasm")
-        .arg("execute")
-        .arg(contract_address)
-        .arg(&json_str)
-        .arg("--gas-prices=0.025uosmo")
-        .arg("--gas=auto")
-        .arg("--gas-adjustment=1.3")
-        .arg("-y")
-        .arg("--keyring-backend=test")
-        .arg("--output=json")
-        .arg(format!("--from={}", WALLET))
-        .arg(format!("--node={}", NODE))
-        .arg(format!("--chain-id={}", TESTNET));
-
-    if !amount.is_empty() {
-        cmd.arg(amount);
+fn run(cli: Cli, matches: &clap::ArgMatches) -> Result<()> {
+    if let Command::Completions { shell } = cli.command {
+        clap_complete::generate(
+            shell,
+            &mut Cli::command(),
+            "osmosis-cli-wrapper",
+            &mut io::stdout(),
+        );
+        return Ok(());
     }
 
-    let output = cmd.output().expect("Failed to execute command");
-
-    print_result(output, json_str);
-}
-
-fn query_contract(contract_name: String, query_json: String) {
-    let output = Command::new("osmosisd")
-        .arg("query")
-        .arg("wasm")
-        .arg("contract-state")
-        .arg("smart")
-        .arg(contract_name)
-        .arg(&query_json)
-        .arg("--output=json")
-        .arg(format!("--node={}", NODE))
-        .output()
-        .expect("Failed to execute command");
-
-    print_result(output, query_json);
-}
-
-fn get_tx_data(tx_hash: &str) {
-    let output = Command::new("osmosisd")
-        .arg("query")
-        .arg("tx")
-        .arg(tx_hash)
-        .arg("--output=json")
-        .arg(format!("--node={}", NODE))
-        .output()
-        .expect("Failed to execute command");
+    let mut network = load_network(NETWORKS, cli.network.as_deref())?;
+    // Reports malformed or duplicate address book entries before any command
+    // runs.
+    network.address_book()?;
 
-    if output.status.success() {
-        let stdout = str::from_utf8(&output.stdout).unwrap();
+    let denoms = DenomRegistry::load(&network.denoms)?;
 
-        let mut parsed_data: Data = serde_json::from_str(stdout).unwrap_or_else(|error| {
-            panic!("Failed to parse JSON: {}", error);
-        });
+    let runner = Osmosisd;
+    let mut stdout = io::stdout();
+    let mut out = Output::new(&mut stdout, cli.output);
 
-        print_tx_details(parsed_data);
-    } else {
-        // Handle command execution failure
-        let stderr = str::from_utf8(&output.stderr).unwrap();
-        eprintln!("Command execution failed: {}", stderr);
-    }
-}
-
-fn print_tx_details(data: Data) {
-    let sender = data.tx.body.messages[0].sender.clone();
-    let messages = data.tx.body.messages;
-    let events_short: String = summarize_events(data.events, true);
-    let logs_short: String = summarize_events(data.logs[0].events.clone(), false);
-
-    println!("___ Tx hash ___");
-    println!("{}", data.txhash);
-
-    println!("___ Sender ___");
-    println!("{}", sender);
+    match cli.command {
+        Command::Execute(args) => {
+            let (_, matches) = matches.subcommand().unwrap();
+            execute(&runner, &network, &denoms, &args, matches, &mut out)
+        }
+        Command::Query(args) => {
+            let contract_address = get_contract_address(&network, &args.contract)?;
+            let json = read_message(&args.source()?, &args.vars.vars()?, &denoms)?;
 
-    println!("___ Messages ___");
-    for message in messages {
-        let json_str = serde_json::to_string_pretty(&message).unwrap();
-        println!("Message:\n{}", json_str);
+            query_contract(&runner, &network, contract_address, json, &mut out)
+        }
+        Command::CreditAccount(args) => {
+            let (_, matches) = matches.subcommand().unwrap();
+            credit_account(&runner, &network, &denoms, &args, matches, &mut out)
+        }
+        Command::Account { id } => {
+            let report = account_report(&runner, &network, &denoms, &id)?;
+            print_account(&report, &mut out)
+        }
+        Command::Accounts {
+            owner,
+            positions,
+            page_size,
+        } => {
+            let report = accounts_report(
+                &runner,
+                &network,
+                &denoms,
+                owner.as_deref(),
+                page_size,
+                positions,
+            )?;
+            print_accounts(&report, &mut out)
+        }
+        Command::Markets { page_size } => {
+            let report = markets_report(&runner, &network, &denoms, page_size)?;
+            print_markets(&report, &mut out)
+        }
+        Command::Analyze(args) => get_tx_data(
+            &runner,
+            &network,
+            &denoms,
+            &args.tx,
+            args.raw_events,
+            &mut out,
+        ),
+        Command::List { contract } => {
+            let actions = list_actions(MESSAGES, network.address_book()?, contract.as_deref())?;
+            print_actions(&actions, &mut out)
+        }
+        Command::Addr { action } => match action.unwrap_or(AddrCommand::List) {
+            AddrCommand::List => print_entries(network.address_book()?.entries(), &mut out),
+            AddrCommand::Lookup { name_or_address } => {
+                let entry = network
+                    .address_book()?
+                    .lookup(&name_or_address)
+                    .ok_or_else(|| {
+                        Error::config(format!("Unknown name or address: {}", name_or_address))
+                    })?;
+                print_entries(std::slice::from_ref(entry), &mut out)
+            }
+            AddrCommand::Add { name, address } => {
+                let entry = add_wallet(&mut network, &name, &address)?;
+                print_entries(&[entry], &mut out)
+            }
+        },
+        Command::Completions { .. } => unreachable!("handled above"),
     }
-
-    println!("___ Events ___");
-    println!("{}", events_short);
-
-    println!("___  Logs ___ ");
-    println!("{}", logs_short);
 }
 
-fn summarize_events(mut events: Vec<Event>, encoding: bool) -> String {
-    let mut events_short: String = "".to_string();
-
-    events.iter_mut().for_each(|event| {
-        if event.event_type != "tx" {
-            events_short += format!("--> {}( ", event.event_type).as_str();
-            for attribute in &mut event.attributes {
-                let mut value = &attribute.value;
-                let key = &attribute.key;
-
-                if encoding {
-                    let decoded_value = decode(&attribute.value);
-                    let decoded_key = decode(&attribute.key);
-
-                    let decoded_value_s = String::from_utf8_lossy(&decoded_value).into_owned();
-                    let decoded_key_s = String::from_utf8_lossy(&decoded_key).into_owned();
+fn execute(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    args: &ExecuteArgs,
+    matches: &clap::ArgMatches,
+    out: &mut Output,
+) -> Result<()> {
+    let vars = args.vars.vars()?;
+    let mut messages = message_args(matches)?;
+
+    if messages.len() > 1 {
+        if args.simulate {
+            return Err(Error::config("--simulate takes a single message"));
+        }
 
-                    let contract_name =
-                        get_contract_name(decoded_value_s.as_str()).unwrap_or("".to_owned());
-                    if !contract_name.is_empty() {
-                        let formated = format!("{} ({})", decoded_value_s, contract_name);
-                        events_short += format!("{}: {}, ", decoded_key_s, formated).as_str();
-                    } else {
-                        events_short +=
-                            format!("{}: {}, ", decoded_key_s, decoded_value_s).as_str();
-                    }
-                } else {
-                    let contract_name = get_contract_name(value).unwrap_or("".to_owned());
-                    if !contract_name.is_empty() {
-                        let formated = format!("{} ({})", value, contract_name);
-                        events_short += format!("{}: {}, ", key, formated).as_str();
-                    } else {
-                        events_short += format!("{}: {}, ", key, value).as_str();
-                    }
-                }
-            }
-            events_short.truncate(events_short.len() - 2);
-            events_short += " )\n";
+        let gas = args
+            .gas
+            .ok_or_else(|| Error::config("Executing several messages needs a --gas limit"))?;
+
+        let mut msgs = Vec::new();
+        for message in messages {
+            let msg = read_message(&message.source, &vars, denoms)?;
+            let amount = message
+                .amount
+                .map(|amount| denoms.parse_coins(&amount))
+                .transpose()?;
+
+            msgs.push(ExecuteMsg {
+                contract: get_contract_address(network, &message.contract)?,
+                funds: message_funds(&message.contract, &msg, amount)?,
+                msg,
+            });
         }
-    });
-    events_short.clone()
-}
 
-fn decode(encoded: &str) -> Vec<u8> {
-    match base64::decode(encoded) {
-        Ok(decoded) => decoded,
-        Err(_) => encoded.as_bytes().to_vec(),
+        return execute_msgs(runner, network, denoms, msgs, gas, args.wait.wait(), out);
     }
-}
 
-fn print_result(output: Output, json_str: String) {
-    let stdout_str = String::from_utf8(output.stdout).unwrap();
-    let stderr_str = String::from_utf8(output.stderr).unwrap();
+    // A single message goes through `tx wasm execute --gas=auto`.
+    if args.gas.is_some() {
+        return Err(Error::config(
+            "--gas is only used when executing several messages",
+        ));
+    }
 
-    println!("Input: \n {}\n", json_str);
-    if let Ok(json) = serde_json::from_str::<Value>(&stdout_str) {
-        println!("Output:\n{}", serde_json::to_string_pretty(&json).unwrap());
-    } else if !stderr_str.is_empty() {
-        println!("Error:\n{}", stderr_str);
+    let message = messages.remove(0);
+    let contract_address = get_contract_address(network, &message.contract)?;
+    let json = read_message(&message.source, &vars, denoms)?;
+    let amount = message
+        .amount
+        .map(|amount| denoms.parse_coins(&amount))
+        .transpose()?;
+    let amount = amount_flag(&message_funds(&message.contract, &json, amount)?);
+
+    if args.simulate {
+        simulate_tx(runner, network, contract_address, json, amount, out)
     } else {
-        println!("Output:\n{}", stdout_str);
+        execute_tx(
+            runner,
+            network,
+            denoms,
+            contract_address,
+            json,
+            amount,
+            args.wait.wait(),
+            out,
+        )
     }
 }
 
-fn get_contract_address(contract_name: &str) -> String {
-    let mut file = File::open(CONTRACTS).expect("Unable to open file");
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Unable to read file");
-
-    let json: Value = serde_json::from_str(&contents).expect("Unable to parse JSON");
-    json[contract_name]
-        .as_str()
-        .map(|s| s.to_owned())
-        .expect("Invalid contract name")
-}
-
-fn get_contract_name(contract_address: &str) -> Option<String> {
-    let mut file = File::open(CONTRACTS).expect("Unable to open file");
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Unable to read file");
-
-    let json: Value = serde_json::from_str(&contents).expect("Unable to parse JSON");
-
-    if let Some(map) = json.as_object() {
-        for (key, value) in map.iter() {
-            if let Some(address) = value.as_str() {
-                if address == contract_address {
-                    return Some(key.to_owned());
-                }
-            }
-        }
-    }
-
-    None
+fn credit_account(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    args: &CreditAccountArgs,
+    matches: &clap::ArgMatches,
+    out: &mut Output,
+) -> Result<()> {
+    let msg =
+        CreditManagerMsg::update_credit_account(&args.account, credit_actions(matches, denoms)?)?;
+
+    execute_credit_account(
+        runner,
+        network,
+        denoms,
+        &msg,
+        args.simulate,
+        args.wait.wait(),
+        out,
+    )
 }
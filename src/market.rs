@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use std::io::Write;
+
+use crate::credit::RED_BANK;
+use crate::denom::DenomRegistry;
+use crate::error::{Error, Result};
+use crate::network::Network;
+use crate::output::Output;
+use crate::runner::Runner;
+use crate::{get_contract_address, smart_query};
+
+pub static ORACLE: &str = "oracle";
+
+/// The redbank stores totals divided by their index and multiplied by this.
+static SCALING_FACTOR: f64 = 1_000_000.0;
+
+/// A redbank market as its `market` and `markets` queries return it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Market {
+    pub denom: String,
+    pub max_loan_to_value: String,
+    pub liquidation_threshold: String,
+    pub borrow_rate: String,
+    pub liquidity_rate: String,
+    pub borrow_index: String,
+    pub liquidity_index: String,
+    pub collateral_total_scaled: String,
+    pub debt_total_scaled: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct PriceResponse {
+    price: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OracleConfig {
+    base_denom: String,
+}
+
+/// A market with its totals in base units and, when the oracle has a price
+/// for it, its deposits valued in the report's `tvl_unit`.
+#[derive(Serialize, Debug)]
+pub struct MarketReport {
+    pub denom: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub deposits: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposits_display: Option<String>,
+    pub borrows: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub borrows_display: Option<String>,
+    pub utilization: f64,
+    pub liquidity_rate: String,
+    pub borrow_rate: String,
+    pub max_loan_to_value: String,
+    pub liquidation_threshold: String,
+    pub price: Option<String>,
+    pub tvl: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MarketsReport {
+    pub markets: Vec<MarketReport>,
+    /// Sum over the markets that have a price.
+    pub tvl: f64,
+    /// `USD` with the network's `usd_denom`, otherwise the symbol of the
+    /// oracle's base denom.
+    pub tvl_unit: String,
+}
+
+/// Pages through every redbank market, `page_size` at a time, and prices
+/// each with the oracle. A market the oracle has no price for is still
+/// listed, without a TVL. TVL is in USD when the network names a
+/// `usd_denom`, otherwise in display units of the oracle's base denom.
+pub fn markets_report(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    page_size: u32,
+) -> Result<MarketsReport> {
+    let red_bank = get_contract_address(network, RED_BANK)?;
+    let oracle = get_contract_address(network, ORACLE)?;
+
+    let mut markets: Vec<Market> = Vec::new();
+    loop {
+        let mut query = json!({"markets": {"limit": page_size}});
+        if let Some(last) = markets.last() {
+            query["markets"]["start_after"] = Value::String(last.denom.clone());
+        }
+
+        // The redbank caps `limit`, so a short page is not necessarily the
+        // last one.
+        let page: Vec<Market> = smart_query(runner, network, &red_bank, &query)?;
+        if page.is_empty() {
+            break;
+        }
+        markets.extend(page);
+    }
+
+    let (tvl_unit, unit_value) = tvl_unit(runner, network, denoms, &oracle)?;
+
+    let mut reports = Vec::new();
+    for market in markets {
+        let deposits = underlying(&market.collateral_total_scaled, &market.liquidity_index)?;
+        let borrows = underlying(&market.debt_total_scaled, &market.borrow_index)?;
+
+        let price = price(runner, network, &oracle, &market.denom)?;
+        let tvl = match &price {
+            Some(price) => Some(deposits * decimal(price)? / unit_value),
+            None => None,
+        };
+
+        let utilization = if deposits > 0.0 {
+            borrows / deposits
+        } else {
+            0.0
+        };
+
+        let deposits = format!("{:.0}", deposits);
+        let borrows = format!("{:.0}", borrows);
+        reports.push(MarketReport {
+            symbol: denoms.by_denom(&market.denom).map(|d| d.symbol.clone()),
+            utilization,
+            deposits_display: denoms.display(&deposits, &market.denom),
+            borrows_display: denoms.display(&borrows, &market.denom),
+            deposits,
+            borrows,
+            liquidity_rate: market.liquidity_rate,
+            borrow_rate: market.borrow_rate,
+            max_loan_to_value: market.max_loan_to_value,
+            liquidation_threshold: market.liquidation_threshold,
+            price,
+            tvl,
+            denom: market.denom,
+        });
+    }
+
+    Ok(MarketsReport {
+        tvl: reports.iter().filter_map(|market| market.tvl).sum(),
+        markets: reports,
+        tvl_unit,
+    })
+}
+
+/// Prints one row per market and the total TVL.
+pub fn print_markets(report: &MarketsReport, out: &mut Output) -> Result<()> {
+    if !out.is_text() {
+        return out.emit(report);
+    }
+
+    let header = [
+        "market",
+        "deposits",
+        "borrows",
+        "utilization",
+        "deposit APR",
+        "borrow APR",
+        "max LTV",
+        "liq. threshold",
+        "price",
+        &format!("TVL ({})", report.tvl_unit),
+    ]
+    .map(str::to_owned)
+    .to_vec();
+    let amount = |amount: &str, display: &Option<String>| match display {
+        Some(display) => display.clone(),
+        None => amount.to_owned(),
+    };
+    let rows: Vec<Vec<String>> = std::iter::once(header)
+        .chain(report.markets.iter().map(|market| {
+            vec![
+                market
+                    .symbol
+                    .clone()
+                    .unwrap_or_else(|| market.denom.clone()),
+                amount(&market.deposits, &market.deposits_display),
+                amount(&market.borrows, &market.borrows_display),
+                format!("{:.2}%", market.utilization * 100.0),
+                market.liquidity_rate.clone(),
+                market.borrow_rate.clone(),
+                market.max_loan_to_value.clone(),
+                market.liquidation_threshold.clone(),
+                market.price.clone().unwrap_or_else(|| "-".to_owned()),
+                match market.tvl {
+                    Some(tvl) => format!("{:.2}", tvl),
+                    None => "-".to_owned(),
+                },
+            ]
+        }))
+        .collect();
+    out.write_table(&rows)?;
+
+    writeln!(out)?;
+    writeln!(out, "TVL: {:.2} {}", report.tvl, report.tvl_unit)?;
+
+    Ok(())
+}
+
+/// The unit TVL and account values are reported in and what one of it is
+/// worth in base units of the oracle's base denom.
+pub(crate) fn tvl_unit(
+    runner: &dyn Runner,
+    network: &Network,
+    denoms: &DenomRegistry,
+    oracle: &str,
+) -> Result<(String, f64)> {
+    if let Some(usd_denom) = &network.usd_denom {
+        let usd = denoms.by_denom(usd_denom).ok_or_else(|| {
+            Error::config(format!(
+                "usd_denom {} is not in the denoms file {}",
+                usd_denom, network.denoms
+            ))
+        })?;
+        let price = price(runner, network, oracle, usd_denom)?.ok_or_else(|| {
+            Error::config(format!(
+                "The oracle has no price for usd_denom {}",
+                usd_denom
+            ))
+        })?;
+        return Ok((
+            "USD".to_owned(),
+            decimal(&price)? * 10f64.powi(usd.exponent as i32),
+        ));
+    }
+
+    let config: OracleConfig = smart_query(runner, network, oracle, &json!({"config": {}}))?;
+    Ok(match denoms.by_denom(&config.base_denom) {
+        Some(base) => (base.symbol.clone(), 10f64.powi(base.exponent as i32)),
+        None => (config.base_denom, 1.0),
+    })
+}
+
+/// The oracle's price for `denom`, or `None` when it has no price source
+/// for it. Any other failure, such as an unreachable node, is an error.
+pub(crate) fn price(
+    runner: &dyn Runner,
+    network: &Network,
+    oracle: &str,
+    denom: &str,
+) -> Result<Option<String>> {
+    match smart_query::<PriceResponse>(runner, network, oracle, &json!({"price": {"denom": denom}}))
+    {
+        Ok(price) => Ok(Some(price.price)),
+        Err(Error::Osmosisd { stderr, .. }) if price_not_found(&stderr) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether the oracle refused a price query for lack of a price source. It
+/// reports `price source not found` or `mars_oracle_base::PriceSource not
+/// found`, depending on the version.
+fn price_not_found(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase().replace([' ', '_'], "");
+    stderr.contains("pricesource") && stderr.contains("notfound")
+}
+
+pub(crate) fn decimal(value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|_| Error::config(format!("Invalid decimal: {}", value)))
+}
+
+/// Turns a scaled total back into base units with its index.
+fn underlying(scaled: &str, index: &str) -> Result<f64> {
+    Ok(decimal(scaled)? * decimal(index)? / SCALING_FACTOR)
+}
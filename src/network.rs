@@ -0,0 +1,91 @@
+use serde::Deserialize;
+
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::address::{AddressBook, ADDRESSES};
+use crate::denom::DENOMS;
+use crate::error::{Error, Result};
+
+pub static NETWORKS: &str = "config/networks.toml";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Network {
+    pub rpc: String,
+    pub chain_id: String,
+    pub gas_price: f64,
+    pub fee_denom: String,
+    pub keyring_backend: String,
+    pub signer: String,
+    pub contracts: String,
+    /// Denom registry for display amounts such as `1.5OSMO`.
+    #[serde(default = "default_denoms")]
+    pub denoms: String,
+    /// Named wallets for the address book.
+    #[serde(default = "default_addresses")]
+    pub addresses: String,
+    /// Bech32 prefix of the chain's addresses.
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+    /// A USD stablecoin the oracle prices, used to value TVL in USD.
+    #[serde(default)]
+    pub usd_denom: Option<String>,
+    #[serde(skip)]
+    book: OnceCell<AddressBook>,
+}
+
+fn default_denoms() -> String {
+    DENOMS.to_owned()
+}
+
+fn default_addresses() -> String {
+    ADDRESSES.to_owned()
+}
+
+fn default_prefix() -> String {
+    "osmo".to_owned()
+}
+
+#[derive(Deserialize, Debug)]
+struct Profiles {
+    default: String,
+    networks: BTreeMap<String, Network>,
+}
+
+impl Network {
+    /// Gas price in the `<amount><denom>` form expected by `--gas-prices`.
+    pub fn gas_prices(&self) -> String {
+        format!("{}{}", self.gas_price, self.fee_denom)
+    }
+
+    /// The network's address book, read from disk on first use only.
+    pub fn address_book(&self) -> Result<&AddressBook> {
+        if let Some(book) = self.book.get() {
+            return Ok(book);
+        }
+
+        let book = AddressBook::load(self)?;
+        Ok(self.book.get_or_init(|| book))
+    }
+
+    /// Replaces the cached address book, e.g. after adding a wallet to it.
+    pub(crate) fn set_address_book(&mut self, book: AddressBook) {
+        self.book = OnceCell::from(book);
+    }
+}
+
+/// Loads the profile called `name` from the networks file, or the file's
+/// `default` profile when no name is given.
+pub fn load_network(path: &str, name: Option<&str>) -> Result<Network> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Error::io(format!("Unable to read networks file {}", path), e))?;
+    let mut profiles: Profiles = toml::from_str(&contents)
+        .map_err(|e| Error::config(format!("Unable to parse networks file {}: {}", path, e)))?;
+
+    let name = name.unwrap_or(&profiles.default).to_owned();
+    profiles
+        .networks
+        .remove(&name)
+        .ok_or_else(|| Error::config(format!("Unknown network: {}", name)))
+}
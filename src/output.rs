@@ -0,0 +1,85 @@
+use serde::Serialize;
+
+use std::io::{self, Write};
+
+use crate::error::{Error, Result};
+
+/// How command results are written, chosen with `--output`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable sections and tables.
+    Text,
+    Json,
+    Yaml,
+}
+
+/// Where a command writes its result. Text is written through the `Write`
+/// impl; structured formats get a single document from `emit`.
+pub struct Output<'a> {
+    writer: &'a mut dyn Write,
+    format: OutputFormat,
+}
+
+impl<'a> Output<'a> {
+    pub fn new(writer: &'a mut dyn Write, format: OutputFormat) -> Self {
+        Output { writer, format }
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format == OutputFormat::Text
+    }
+
+    /// Writes `value` as one JSON or YAML document. In text mode it is
+    /// printed as pretty JSON.
+    pub fn emit<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        match self.format {
+            OutputFormat::Text | OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(value)
+                    .map_err(|e| Error::json("Failed to format output", e))?;
+                writeln!(self.writer, "{}", json)?;
+            }
+            OutputFormat::Yaml => {
+                let yaml = serde_yaml::to_string(value)
+                    .map_err(|e| Error::config(format!("Failed to format output: {}", e)))?;
+                write!(self.writer, "{}", yaml)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes rows as left aligned columns two spaces apart. The first row
+    /// is usually the header.
+    pub fn write_table(&mut self, rows: &[Vec<String>]) -> Result<()> {
+        let mut widths: Vec<usize> = Vec::new();
+        for row in rows {
+            for (i, cell) in row.iter().enumerate() {
+                match widths.get_mut(i) {
+                    Some(width) => *width = (*width).max(cell.len()),
+                    None => widths.push(cell.len()),
+                }
+            }
+        }
+
+        for row in rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            writeln!(self.writer, "{}", cells.join("  ").trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for Output<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,127 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::process::Command;
+
+/// What an `osmosisd` invocation left behind.
+#[derive(Debug, Clone)]
+pub struct CmdOutput {
+    pub status: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CmdOutput {
+    pub fn success(&self) -> bool {
+        self.status == 0
+    }
+}
+
+/// Runs `osmosisd` with the given arguments.
+pub trait Runner {
+    fn run(&self, args: &[String]) -> io::Result<CmdOutput>;
+}
+
+/// Spawns the real `osmosisd` binary from `PATH`.
+pub struct Osmosisd;
+
+impl Runner for Osmosisd {
+    fn run(&self, args: &[String]) -> io::Result<CmdOutput> {
+        let output = Command::new("osmosisd").args(args).output()?;
+
+        Ok(CmdOutput {
+            status: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// A recorded `osmosisd` call. `stdout` may be given as a JSON document
+/// instead of a string to keep fixture files readable.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Fixture {
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub status: i32,
+    #[serde(default)]
+    pub stdout: Value,
+    #[serde(default)]
+    pub stderr: String,
+}
+
+impl Fixture {
+    fn matches(&self, args: &[String]) -> bool {
+        self.args.len() == args.len()
+            && self
+                .args
+                .iter()
+                .zip(args)
+                .all(|(pattern, arg)| match pattern.strip_suffix('*') {
+                    Some(prefix) => arg.starts_with(prefix),
+                    None => pattern == arg,
+                })
+    }
+}
+
+/// Replays recorded `osmosisd` calls, matched on their exact arguments. A
+/// fixture argument ending in `*` matches any argument with that prefix, for
+/// temp file paths. Fixtures recorded for the same arguments are replayed in
+/// order, the last one repeating, so polling loops can be exercised.
+pub struct Fixtures {
+    fixtures: Vec<Fixture>,
+    used: RefCell<Vec<bool>>,
+}
+
+impl Fixtures {
+    pub fn new(fixtures: Vec<Fixture>) -> Self {
+        let used = RefCell::new(vec![false; fixtures.len()]);
+        Fixtures { fixtures, used }
+    }
+
+    /// Reads a JSON array of fixtures from `path`.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let fixtures = serde_json::from_str(&contents)?;
+
+        Ok(Fixtures::new(fixtures))
+    }
+}
+
+impl Runner for Fixtures {
+    fn run(&self, args: &[String]) -> io::Result<CmdOutput> {
+        let mut used = self.used.borrow_mut();
+        let matching: Vec<usize> = (0..self.fixtures.len())
+            .filter(|&i| self.fixtures[i].matches(args))
+            .collect();
+
+        let index = matching
+            .iter()
+            .find(|&&i| !used[i])
+            .or_else(|| matching.last())
+            .copied()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no fixture for: osmosisd {}", args.join(" ")),
+                )
+            })?;
+        used[index] = true;
+        let fixture = &self.fixtures[index];
+
+        let stdout = match &fixture.stdout {
+            Value::Null => "".to_owned(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        Ok(CmdOutput {
+            status: fixture.status,
+            stdout,
+            stderr: fixture.stderr.clone(),
+        })
+    }
+}
@@ -0,0 +1,160 @@
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::denom::{to_base, DenomRegistry};
+use crate::error::{Error, Result};
+
+pub type Vars = BTreeMap<String, String>;
+
+/// Prefix of environment variables that fill placeholders, e.g.
+/// `OSMOSIS_VAR_ACCOUNT_ID` for `{{account_id}}`.
+pub static ENV_PREFIX: &str = "OSMOSIS_VAR_";
+
+/// Parses a `key=value` pair as given to `--var`.
+pub fn parse_var(pair: &str) -> Result<(String, String)> {
+    match pair.split_once('=') {
+        Some((key, value)) if is_name(key.trim()) => Ok((key.trim().to_owned(), value.to_owned())),
+        _ => Err(Error::config(format!(
+            "--var expects key=value, got {}",
+            pair
+        ))),
+    }
+}
+
+/// Path of the defaults file next to a message file:
+/// `execute-deposit.json` -> `execute-deposit.vars.json`.
+pub fn sidecar_path(json_path: &str) -> String {
+    let path = Path::new(json_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    path.with_file_name(format!("{}.vars.json", stem))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Reads the defaults declared for a message file, if it has any.
+pub fn load_defaults(json_path: &str) -> Result<Vars> {
+    let path = sidecar_path(json_path);
+    if !Path::new(&path).exists() {
+        return Ok(Vars::new());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| Error::io(format!("Failed to read variables file {}", path), e))?;
+    let json: BTreeMap<String, Value> = serde_json::from_str(&contents)
+        .map_err(|e| Error::json(format!("Failed to parse variables file {}", path), e))?;
+
+    Ok(json
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => (key, s),
+            other => (key, other.to_string()),
+        })
+        .collect())
+}
+
+/// Replaces every `{{name}}` in `template`, looking the name up in `vars`,
+/// then the environment, then `defaults`. `{{name|amount}}` and
+/// `{{name|denom}}` turn a display amount such as `1.5OSMO` into its base
+/// amount and denom. Placeholders sit inside JSON strings, so values are
+/// JSON-escaped. Fails with the list of names that could not be resolved.
+pub fn render(
+    template: &str,
+    vars: &Vars,
+    defaults: &Vars,
+    denoms: &DenomRegistry,
+) -> std::result::Result<String, Vec<String>> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut unresolved: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        let placeholder = rest[start + 2..end].trim();
+        let (name, filter) = match placeholder.split_once('|') {
+            Some((name, filter)) => (name.trim(), Some(filter.trim())),
+            None => (placeholder, None),
+        };
+
+        rendered += &rest[..start];
+        if !is_name(name) || !filter.is_none_or(is_filter) {
+            rendered += &rest[start..end + 2];
+        } else {
+            let value = lookup(name, vars, defaults)
+                .ok_or_else(|| name.to_owned())
+                .and_then(|value| match filter {
+                    Some(filter) => apply(filter, &value, denoms)
+                        .ok_or_else(|| format!("{} ({} is not an amount)", name, value)),
+                    None => Ok(value),
+                });
+
+            match value {
+                Ok(value) => rendered += &escape(&value),
+                Err(name) => {
+                    if !unresolved.contains(&name) {
+                        unresolved.push(name);
+                    }
+                }
+            }
+        }
+        rest = &rest[end + 2..];
+    }
+    rendered += rest;
+
+    if !unresolved.is_empty() {
+        return Err(unresolved);
+    }
+
+    Ok(rendered)
+}
+
+fn lookup(name: &str, vars: &Vars, defaults: &Vars) -> Option<String> {
+    vars.get(name)
+        .cloned()
+        .or_else(|| env::var(format!("{}{}", ENV_PREFIX, name.to_uppercase())).ok())
+        .or_else(|| defaults.get(name).cloned())
+}
+
+/// `value` as the inside of a JSON string literal.
+fn escape(value: &str) -> String {
+    let quoted = Value::String(value.to_owned()).to_string();
+    quoted[1..quoted.len() - 1].to_owned()
+}
+
+fn is_filter(filter: &str) -> bool {
+    filter == "amount" || filter == "denom"
+}
+
+/// Applies an `amount` or `denom` filter to a value like `1.5OSMO`, `OSMO`,
+/// `1500000uosmo` or `1500000`.
+fn apply(filter: &str, value: &str, denoms: &DenomRegistry) -> Option<String> {
+    let coin = || {
+        if value.chars().all(|c| c.is_ascii_digit()) {
+            to_base(value, 0).map(|amount| (amount, "".to_owned()))
+        } else {
+            let fund = denoms.parse_coin(value).ok()?;
+            Some((fund.amount, fund.denom))
+        }
+    };
+
+    match filter {
+        "amount" => coin().map(|(amount, _)| amount),
+        _ => match denoms.by_symbol(value) {
+            Some(known) => Some(known.denom.clone()),
+            None if value.starts_with(|c: char| c.is_ascii_digit()) => {
+                coin().map(|(_, denom)| denom)
+            }
+            None => Some(value.to_owned()),
+        },
+    }
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use osmosis_cli_wrapper::account::{
+    account_report, accounts_report, print_account, print_accounts, AssetKind,
+};
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use osmosis_cli_wrapper::runner::{Fixture, Fixtures};
+
+use serde_json::json;
+
+use std::fs;
+
+use common::{denoms, fixtures, network, OTHER, RED_BANK, USER};
+
+fn recorded(name: &str) -> Vec<Fixture> {
+    let contents = fs::read_to_string(format!("tests/fixtures/{}.json", name)).unwrap();
+    serde_json::from_str(&contents).unwrap()
+}
+
+#[test]
+fn account_values_collateral_and_debt() {
+    let runner = fixtures("account");
+
+    let report = account_report(&runner, &network(), &denoms(), "10").unwrap();
+
+    assert_eq!(report.red_bank, RED_BANK);
+    let kinds: Vec<AssetKind> = report.assets.iter().map(|asset| asset.kind).collect();
+    assert_eq!(
+        kinds,
+        [AssetKind::Deposit, AssetKind::Lend, AssetKind::Debt]
+    );
+    assert_eq!(report.value_unit, "USD");
+    assert_eq!(report.assets[1].value, Some(2.5));
+    assert_eq!(report.assets[2].shares.as_deref(), Some("2000000000000"));
+    assert_eq!(report.collateral_value, 7.5);
+    assert_eq!(report.debt_value, 1.0);
+    assert!((report.ltv.unwrap() - 0.133333).abs() < 1e-6);
+    assert!((report.liquidation_threshold.unwrap() - 0.59).abs() < 1e-9);
+    assert!((report.health_factor.unwrap() - 4.425).abs() < 1e-9);
+}
+
+#[test]
+fn account_prints_a_table_and_health() {
+    let runner = fixtures("account");
+    let report = account_report(&runner, &network(), &denoms(), "10").unwrap();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    print_account(&report, &mut out).unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("___ Credit account 10 ___"));
+    assert!(out.contains("kind     denom  amount              price  value (USD)  max LTV"));
+    assert!(out.contains("lend     uion   2000000 (2 ION)     2.5    2.50         0.5      0.55"));
+    assert!(out.contains("Collateral value:      7.50 USD"));
+    assert!(out.contains("Health factor:         4.4250"));
+}
+
+#[test]
+fn account_falls_back_to_the_contracts_file() {
+    let mut recorded = recorded("account");
+    recorded[0].stdout = json!({"data": {}});
+    let runner = Fixtures::new(recorded);
+
+    let report = account_report(&runner, &network(), &denoms(), "11").unwrap();
+
+    assert_eq!(report.red_bank, RED_BANK);
+    assert_eq!(report.collateral_value, 2.5);
+    assert_eq!(report.ltv, Some(0.0));
+    assert_eq!(report.health_factor, None);
+}
+
+#[test]
+fn assets_without_a_price_are_left_out_of_the_totals() {
+    let mut recorded = recorded("account");
+    let uion = recorded
+        .iter_mut()
+        .find(|fixture| fixture.args[5] == r#"{"price":{"denom":"uion"}}"#)
+        .unwrap();
+    uion.status = 1;
+    uion.stdout = json!(null);
+    uion.stderr = "Error: rpc error: code = Unknown desc = Generic error: Querier contract error: price source not found for denom uion".to_owned();
+    let runner = Fixtures::new(recorded);
+
+    let report = account_report(&runner, &network(), &denoms(), "10").unwrap();
+    assert_eq!(report.assets[1].price, None);
+    assert_eq!(report.assets[1].value, None);
+    assert_eq!(report.collateral_value, 5.0);
+
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+    print_account(&report, &mut out).unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("lend     uion   2000000 (2 ION)     -      -"));
+}
+
+#[test]
+fn unknown_account_is_an_osmosisd_error() {
+    let runner = fixtures("account");
+
+    let error = account_report(&runner, &network(), &denoms(), "99").unwrap_err();
+
+    assert!(matches!(error, Error::Osmosisd { status: 1, .. }));
+}
+
+#[test]
+fn accounts_of_the_signer_follow_the_pages() {
+    let runner = fixtures("accounts");
+
+    // The fixture accountNft returns at most 2 tokens per page.
+    let report = accounts_report(&runner, &network(), &denoms(), None, 3, false).unwrap();
+
+    assert_eq!(report.owner, USER);
+    let ids: Vec<&str> = report
+        .accounts
+        .iter()
+        .map(|account| account.account_id.as_str())
+        .collect();
+    assert_eq!(ids, ["10", "11", "12"]);
+    assert!(report.accounts[0].position.is_none());
+}
+
+#[test]
+fn accounts_summarize_their_positions() {
+    let mut calls = recorded("accounts");
+    calls.extend(recorded("account"));
+    let runner = Fixtures::new(calls);
+
+    let report = accounts_report(&runner, &network(), &denoms(), Some("wallet"), 10, true).unwrap();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+    print_accounts(&report, &mut out).unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(&format!("___ Credit accounts of {} (wallet): 2 ___", USER)));
+    assert!(out.contains("10       7.50                    1.00              4.4250"));
+    assert!(out.contains("11       2.50                    0.00              -"));
+}
+
+#[test]
+fn accounts_owner_must_be_a_name_or_address() {
+    let runner = fixtures("accounts");
+    let list = |owner| accounts_report(&runner, &network(), &denoms(), Some(owner), 10, false);
+
+    assert!(list(OTHER).unwrap().accounts.is_empty());
+    assert!(list("nobody")
+        .unwrap_err()
+        .to_string()
+        .starts_with("Unknown name or invalid address nobody: "));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use osmosis_cli_wrapper::actions::{action_path, list_actions, MessageKind, MESSAGES};
+use osmosis_cli_wrapper::cli::{message_args, Cli};
+use osmosis_cli_wrapper::MessageSource;
+
+use clap::CommandFactory;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use common::network;
+
+#[test]
+fn actions_resolve_to_message_files() {
+    assert_eq!(
+        action_path(MESSAGES, "redbank", MessageKind::Execute, "deposit").unwrap(),
+        "json/redbank/execute-deposit.json"
+    );
+    assert_eq!(
+        action_path(MESSAGES, "creditManager", MessageKind::Query, "config").unwrap(),
+        "json/creditManager/query-config.json"
+    );
+
+    let error = action_path(MESSAGES, "redbank", MessageKind::Execute, "borrow").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "No execute-borrow action for redbank: json/redbank/execute-borrow.json does not exist"
+    );
+}
+
+#[test]
+fn execute_pairs_actions_with_their_contract() {
+    let matches = Cli::command()
+        .try_get_matches_from([
+            "osmosis-cli-wrapper",
+            "execute",
+            "--contract",
+            "redbank",
+            "--action",
+            "deposit",
+            "--amount",
+            "1OSMO",
+            "--contract",
+            "creditManager",
+            "--action",
+            "create_credit_account",
+        ])
+        .unwrap();
+    let (_, matches) = matches.subcommand().unwrap();
+
+    let messages = message_args(matches).unwrap();
+
+    assert!(matches!(
+        &messages[0].source,
+        MessageSource::File(path) if path == "json/redbank/execute-deposit.json"
+    ));
+    assert_eq!(messages[0].amount.as_deref(), Some("1OSMO"));
+    assert!(matches!(
+        &messages[1].source,
+        MessageSource::File(path) if path == "json/creditManager/execute-create_credit_account.json"
+    ));
+}
+
+#[test]
+fn list_skips_vars_files() {
+    let network = network();
+    let actions = list_actions(MESSAGES, network.address_book().unwrap(), Some("redbank")).unwrap();
+
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].contract, "redbank");
+    assert_eq!(actions[0].execute, ["deposit"]);
+    assert_eq!(
+        actions[0].query,
+        ["market", "user_collaterals", "user_debt", "user_position"]
+    );
+}
+
+#[test]
+fn list_refuses_directories_that_are_not_contracts() {
+    let dir = env::temp_dir().join(format!("osmosis-cli-wrapper-{}-actions", process::id()));
+    fs::create_dir_all(dir.join("redbank")).unwrap();
+    fs::create_dir_all(dir.join("wallet")).unwrap();
+    fs::write(dir.join("redbank").join("execute-deposit.json"), "{}").unwrap();
+
+    let network = network();
+    let book = network.address_book().unwrap();
+    let all = list_actions(&dir.display().to_string(), book, None).map(|_| ());
+    let missing = list_actions(&dir.display().to_string(), book, Some("oracle")).map(|_| ());
+    let _ = fs::remove_dir_all(&dir);
+
+    assert_eq!(
+        all.unwrap_err().to_string(),
+        "Message directories without a contract in the contracts file: wallet"
+    );
+    assert!(missing
+        .unwrap_err()
+        .to_string()
+        .starts_with("No messages for oracle in "));
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use osmosis_cli_wrapper::address::{
+    add_wallet, module_address, validate, AddressBook, Entry, Kind,
+};
+use osmosis_cli_wrapper::error::Error;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use common::{network, OTHER, USER};
+
+#[test]
+fn book_names_contracts_wallets_and_modules() {
+    let book = AddressBook::load(&network()).unwrap();
+
+    assert_eq!(
+        book.name("osmo15ywk53ck3wp6tnqgedfd8cnfx7fuhz9dr583hw8scp0xjgw46m0sf3kyyp"),
+        Some("creditManager")
+    );
+    assert_eq!(book.name(USER), Some("wallet"));
+    assert_eq!(
+        book.address("fee_collector"),
+        Some("osmo17xpfvakm2amg962yls6f84z3kell8c5lczssa0")
+    );
+    assert_eq!(book.lookup("distribution").unwrap().kind, Kind::Module);
+    assert_eq!(module_address("osmo", "distribution").unwrap(), OTHER);
+}
+
+#[test]
+fn addresses_must_be_bech32_with_the_network_prefix() {
+    assert!(validate("osmo", USER).is_ok());
+    assert!(validate("osmo", "osmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yu").is_err());
+    assert!(validate("cosmos", USER).is_err());
+    assert!(validate("osmo", "wallet").is_err());
+    assert!(matches!(module_address("", "gov"), Err(Error::Config(_))));
+    assert!(matches!(
+        module_address("Osmo", "gov"),
+        Err(Error::Config(_))
+    ));
+}
+
+#[test]
+fn malformed_entries_fail_to_load() {
+    let path = env::temp_dir().join(format!("osmosis-cli-wrapper-{}-bad.json", process::id()));
+    fs::write(&path, r#"{"alice": "osmo1notanaddress"}"#).unwrap();
+
+    let mut network = network();
+    network.addresses = path.display().to_string();
+    let error = AddressBook::load(&network).unwrap_err();
+    let _ = fs::remove_file(&path);
+
+    assert!(matches!(error, Error::Config(_)));
+    assert!(error
+        .to_string()
+        .starts_with("Invalid address for alice in "));
+}
+
+#[test]
+fn wallets_are_added_to_the_addresses_file() {
+    let path = env::temp_dir().join(format!(
+        "osmosis-cli-wrapper-{}-wallets.json",
+        process::id()
+    ));
+    let mut network = network();
+    network.addresses = path.display().to_string();
+
+    add_wallet(&mut network, "alice", USER).unwrap();
+    let duplicate = add_wallet(&mut network, "alice", OTHER).unwrap_err();
+    let contract = add_wallet(&mut network, "redbank", OTHER).unwrap_err();
+    let taken = add_wallet(&mut network, "me", USER).unwrap_err();
+    let module = add_wallet(&mut network, "me", OTHER).unwrap_err();
+    let invalid = add_wallet(&mut network, "bob", "cosmos1xyz").unwrap_err();
+    let book = AddressBook::load(&network).unwrap();
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(book.name(USER), Some("alice"));
+    assert_eq!(network.address_book().unwrap().name(USER), Some("alice"));
+    assert_eq!(
+        duplicate.to_string(),
+        format!("alice is already the wallet {}", USER)
+    );
+    assert!(contract
+        .to_string()
+        .starts_with("redbank is already the contract "));
+    assert_eq!(
+        taken.to_string(),
+        format!("{} is already the wallet alice", USER)
+    );
+    assert_eq!(
+        module.to_string(),
+        format!("{} is already the module distribution", OTHER)
+    );
+    assert!(matches!(invalid, Error::Config(_)));
+}
+
+#[test]
+fn duplicate_names_and_addresses_are_refused() {
+    let entry = |name: &str, address: &str| Entry {
+        name: name.to_owned(),
+        address: address.to_owned(),
+        kind: Kind::Wallet,
+    };
+
+    let name = AddressBook::new(vec![entry("alice", USER), entry("alice", OTHER)]).unwrap_err();
+    let address = AddressBook::new(vec![entry("alice", USER), entry("bob", USER)]).unwrap_err();
+
+    assert!(name.to_string().starts_with("Duplicate name alice: "));
+    assert_eq!(
+        address.to_string(),
+        format!("Duplicate address {}: named both alice and bob", USER)
+    );
+}
+
+#[test]
+fn address_book_is_read_once_per_network() {
+    let path = env::temp_dir().join(format!("osmosis-cli-wrapper-{}-once.json", process::id()));
+    fs::write(&path, format!(r#"{{"alice": "{}"}}"#, USER)).unwrap();
+
+    let mut network = network();
+    network.addresses = path.display().to_string();
+    let first = network
+        .address_book()
+        .unwrap()
+        .name(USER)
+        .map(str::to_owned);
+    fs::write(&path, r#"{"alice": "osmo1notanaddress"}"#).unwrap();
+    let second = network
+        .address_book()
+        .unwrap()
+        .name(USER)
+        .map(str::to_owned);
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(first.as_deref(), Some("alice"));
+    assert_eq!(second, first);
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use osmosis_cli_wrapper::get_tx_data;
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use serde_json::Value;
+
+use common::{denoms, fixtures, network};
+
+const BATCH_HASH: &str = "A1B2C3D4E5F60718293A4B5C6D7E8F90A1B2C3D4E5F60718293A4B5C6D7E8F90";
+const SDK50_HASH: &str = "5B0C0A1D2E3F405162738495A6B7C8D9E0F1021324354657687980A1B2C3D4E5";
+const PLAIN_HASH: &str = "C47F0E9B3A2D1C5E6F708192A3B4C5D6E7F8091A2B3C4D5E6F708192A3B4C5D6";
+const REPAY_HASH: &str = "9D8C7B6A5F4E3D2C1B0A99887766554433221100FFEEDDCCBBAA998877665544";
+const TRANSFER_HASH: &str = "0B1C2D3E4F5A6B7C8D9E0F1A2B3C4D5E6F7A8B9C0D1E2F3A4B5C6D7E8F9A0B1C";
+const FAILED_HASH: &str = "E0F1A2B3C4D5E6F708192A3B4C5D6E7F8091A2B3C4D5E6F708192A3B4C5D6E7F";
+
+fn analyze(hash: &str) -> String {
+    analyze_with(hash, false)
+}
+
+fn analyze_with(hash: &str, raw_events: bool) -> String {
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    get_tx_data(
+        &fixtures("analyze"),
+        &network(),
+        &denoms(),
+        hash,
+        raw_events,
+        &mut out,
+    )
+    .unwrap();
+
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn every_message_gets_its_own_log() {
+    let out = analyze(BATCH_HASH);
+
+    let first = out.find("___ Message 0: redbank ___").unwrap();
+    let second = out.find("___ Message 1: creditManager ___").unwrap();
+    assert!(first < second);
+
+    let (first_section, second_section) = out[first..].split_at(second - first);
+    assert!(first_section.contains("action: deposit"));
+    assert!(!first_section.contains("rover/credit-manager/borrow"));
+    assert!(second_section.contains("action: rover/credit-manager/borrow"));
+}
+
+#[test]
+fn tx_without_logs_does_not_panic() {
+    let out = analyze(FAILED_HASH);
+
+    assert!(out.contains("___ Message 0: creditManager ___"));
+    assert!(out.contains("Logs: none\n"));
+    assert!(out.contains(
+        "--> coin_spent( spender: osmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yt (wallet), amount: 6250uosmo (0.00625 OSMO) )"
+    ));
+}
+
+#[test]
+fn sdk50_events_are_grouped_by_msg_index() {
+    let out = analyze(SDK50_HASH);
+
+    let first = out.find("___ Message 0: redbank ___").unwrap();
+    let second = out.find("___ Message 1: creditManager ___").unwrap();
+
+    let (first_section, second_section) = out[first..].split_at(second - first);
+    assert!(first_section.contains("--> wasm( _contract_address: "));
+    assert!(first_section.contains("action: deposit"));
+    assert!(!first_section.contains("msg_index"));
+    assert!(second_section.contains("action: rover/credit-manager/borrow"));
+    assert!(second_section.contains(
+        "--> coin_spent( spender: osmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yt (wallet), amount: 6250uosmo (0.00625 OSMO) )"
+    ));
+}
+
+#[test]
+fn reports_response_format() {
+    let format = |hash: &str| {
+        let mut buf = Vec::new();
+        let mut out = Output::new(&mut buf, OutputFormat::Json);
+        get_tx_data(
+            &fixtures("analyze"),
+            &network(),
+            &denoms(),
+            hash,
+            false,
+            &mut out,
+        )
+        .unwrap();
+
+        let report: Value = serde_json::from_slice(&buf).unwrap();
+        (report["format"].clone(), report["logs"].clone())
+    };
+
+    assert_eq!(format(BATCH_HASH).0, "legacy");
+
+    let (sdk50, logs) = format(SDK50_HASH);
+    assert_eq!(sdk50, "sdk50");
+    assert_eq!(logs.as_array().unwrap().len(), 2);
+    assert_eq!(logs[1]["msg_index"], 1);
+}
+
+#[test]
+fn plain_legacy_events_are_not_decoded() {
+    let out = analyze(PLAIN_HASH);
+    let events = &out[out.find("___ Events ___").unwrap()..];
+
+    // "wasm" happens to be valid base64, "module" is not.
+    assert!(events.contains("module: wasm"));
+    assert!(events.contains("amount: 1000000uosmo"));
+}
+
+#[test]
+fn raw_events_skip_decoding() {
+    let decoded = analyze_with(BATCH_HASH, false);
+    let raw = analyze_with(BATCH_HASH, true);
+
+    assert!(decoded.contains("--> coin_spent( spender: "));
+    let raw_events = &raw[raw.find("___ Events ___").unwrap()..];
+    assert!(raw_events.contains("--> coin_spent( c3BlbmRlcg==: "));
+    assert!(!raw_events.contains("spender: "));
+}
+
+#[test]
+fn raw_events_still_show_the_balance_changes() {
+    let raw = analyze_with(BATCH_HASH, true);
+
+    assert!(raw.ends_with(
+        "___ Balance changes ___\n\
+         wallet         -1006250 uosmo  (-1.00625 OSMO)\n\
+         fee_collector     +6250 uosmo  (+0.00625 OSMO)\n\
+         redbank         +999000 uosmo  (+0.999 OSMO)\n\
+         creditManager     +1000 uosmo  (+0.001 OSMO)\n\n"
+    ));
+}
+
+#[test]
+fn wasm_events_show_the_contract_calls() {
+    let out = analyze(BATCH_HASH);
+
+    assert!(out.contains(
+        "Contracts:\n\
+         creditManager\n\
+         \x20 wasm: rover/credit-manager/borrow\n\
+         \x20   account_id     10\n\
+         \x20   coin_borrowed  1000uosmo (0.001 OSMO)\n\
+         \x20 -> redbank\n\
+         \x20     wasm: borrow\n\
+         \x20       sender         osmo15ywk53ck3wp6tnqgedfd8cnfx7fuhz9dr583hw8scp0xjgw46m0sf3kyyp (creditManager)\n"
+    ));
+}
+
+#[test]
+fn merged_legacy_wasm_events_are_split_per_call() {
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Json);
+    get_tx_data(
+        &fixtures("analyze"),
+        &network(),
+        &denoms(),
+        REPAY_HASH,
+        false,
+        &mut out,
+    )
+    .unwrap();
+
+    let report: Value = serde_json::from_slice(&buf).unwrap();
+    let calls = report["logs"][0]["calls"].as_array().unwrap();
+    let summary: Vec<(&str, u64, &str)> = calls
+        .iter()
+        .map(|call| {
+            (
+                call["label"].as_str().unwrap(),
+                call["depth"].as_u64().unwrap(),
+                call["events"][0]["action"].as_str().unwrap(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("creditManager", 0, "rover/credit-manager/repay"),
+            ("redbank", 1, "repay"),
+            ("creditManager", 1, "callback/refund"),
+        ]
+    );
+    assert_eq!(calls[1]["events"][0]["attributes"][2]["key"], "denom");
+}
+
+#[test]
+fn balance_changes_net_out_per_address() {
+    let out = analyze(BATCH_HASH);
+
+    assert!(out.ends_with(
+        "___ Balance changes ___\n\
+         wallet         -1006250 uosmo  (-1.00625 OSMO)\n\
+         fee_collector     +6250 uosmo  (+0.00625 OSMO)\n\
+         redbank         +999000 uosmo  (+0.999 OSMO)\n\
+         creditManager     +1000 uosmo  (+0.001 OSMO)\n\n"
+    ));
+}
+
+#[test]
+fn balance_changes_fall_back_to_transfer_events() {
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Json);
+    get_tx_data(
+        &fixtures("analyze"),
+        &network(),
+        &denoms(),
+        TRANSFER_HASH,
+        false,
+        &mut out,
+    )
+    .unwrap();
+
+    let report: Value = serde_json::from_slice(&buf).unwrap();
+    let changes: Vec<(&str, &str, &str)> = report["balance_changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| {
+            (
+                change["label"].as_str().unwrap(),
+                change["denom"].as_str().unwrap(),
+                change["amount"].as_str().unwrap(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        changes,
+        vec![
+            ("wallet", "uosmo", "-10006250"),
+            ("fee_collector", "uosmo", "6250"),
+            ("wallet", "uion", "-3"),
+            ("creditManager", "uion", "2"),
+            ("creditManager", "uosmo", "10000000"),
+        ]
+    );
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use osmosis_cli_wrapper::batch::{execute_msgs, unsigned_tx, ExecuteMsg};
+use osmosis_cli_wrapper::denom::parse_base_coins;
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use osmosis_cli_wrapper::runner::Fixtures;
+
+use common::{denoms, network, RED_BANK, USER};
+
+const CREDIT_MANAGER: &str = "osmo15ywk53ck3wp6tnqgedfd8cnfx7fuhz9dr583hw8scp0xjgw46m0sf3kyyp";
+
+fn msgs() -> Vec<ExecuteMsg> {
+    vec![
+        ExecuteMsg {
+            contract: RED_BANK.to_owned(),
+            msg: r#"{"deposit":{}}"#.to_owned(),
+            funds: parse_base_coins("1000000uosmo").unwrap(),
+        },
+        ExecuteMsg {
+            contract: CREDIT_MANAGER.to_owned(),
+            msg: r#"{"update_credit_account":{"account_id":"10","actions":[{"borrow":{"denom":"uosmo","amount":"1000"}}]}}"#.to_owned(),
+            funds: vec![],
+        },
+    ]
+}
+
+#[test]
+fn unsigned_tx_holds_every_message() {
+    let tx = unsigned_tx(&network(), USER, msgs(), 800000).unwrap();
+    let json = serde_json::to_value(&tx).unwrap();
+
+    let messages = json["body"]["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["@type"], "/cosmwasm.wasm.v1.MsgExecuteContract");
+    assert_eq!(messages[0]["sender"], USER);
+    assert_eq!(messages[0]["funds"][0]["amount"], "1000000");
+    assert_eq!(messages[1]["contract"], CREDIT_MANAGER);
+    assert_eq!(
+        messages[1]["msg"]["update_credit_account"]["account_id"],
+        "10"
+    );
+    assert_eq!(json["auth_info"]["fee"]["gas_limit"], "800000");
+    assert_eq!(json["auth_info"]["fee"]["amount"][0]["amount"], "20000");
+    assert_eq!(json["auth_info"]["fee"]["amount"][0]["denom"], "uosmo");
+}
+
+#[test]
+fn execute_msgs_signs_and_broadcasts() {
+    let fixtures = Fixtures::load("tests/fixtures/batch.json").unwrap();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    execute_msgs(
+        &fixtures,
+        &network(),
+        &denoms(),
+        msgs(),
+        800000,
+        None,
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.starts_with("Input: \n {\"deposit\":{}}\n {\"update_credit_account\""));
+    assert!(out.contains(
+        "\"txhash\": \"5D0C9A3E7B1F2468ACE013579BDF2468ACE013579BDF2468ACE013579BDF2468\""
+    ));
+}
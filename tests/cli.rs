@@ -0,0 +1,197 @@
+use clap::{CommandFactory, ErrorKind, Parser};
+use clap_complete::Shell;
+
+use osmosis_cli_wrapper::cli::{message_args, Cli};
+use osmosis_cli_wrapper::output::OutputFormat;
+use osmosis_cli_wrapper::MessageSource;
+
+fn parse(args: &[&str]) -> clap::Result<clap::ArgMatches> {
+    Cli::command().try_get_matches_from(["osmosis-cli-wrapper"].iter().chain(args))
+}
+
+#[test]
+fn cli_definition_is_consistent() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn amounts_follow_their_contract() {
+    let matches = parse(&[
+        "execute",
+        "--contract",
+        "redbank",
+        "--json",
+        "json/redbank/execute-deposit.json",
+        "--amount",
+        "1OSMO",
+        "--contract",
+        "creditManager",
+        "--msg",
+        "{}",
+        "--gas",
+        "600000",
+    ])
+    .unwrap();
+    let (_, matches) = matches.subcommand().unwrap();
+
+    let messages = message_args(matches).unwrap();
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].contract, "redbank");
+    assert_eq!(messages[0].amount.as_deref(), Some("1OSMO"));
+    assert!(matches!(messages[1].source, MessageSource::Inline(_)));
+    assert_eq!(messages[1].amount, None);
+}
+
+#[test]
+fn messages_follow_their_contract() {
+    let messages = |args: &[&str]| {
+        let matches = parse(&[&["execute"], args].concat()).unwrap();
+        let (_, matches) = matches.subcommand().unwrap();
+        message_args(matches).map_err(|e| e.to_string())
+    };
+
+    let single = messages(&["--msg", "{}", "--contract", "redbank"]).unwrap();
+    assert_eq!(single[0].contract, "redbank");
+
+    assert_eq!(
+        messages(&[
+            "--contract",
+            "redbank",
+            "--contract",
+            "creditManager",
+            "--json",
+            "x.json",
+            "--amount",
+            "1uosmo",
+            "--json",
+            "y.json",
+        ])
+        .unwrap_err(),
+        "--contract creditManager has more than one --json, --msg or --action"
+    );
+    assert_eq!(
+        messages(&[
+            "--contract",
+            "redbank",
+            "--msg",
+            "{}",
+            "--contract",
+            "oracle"
+        ])
+        .unwrap_err(),
+        "--contract oracle needs a --json, --msg or --action"
+    );
+}
+
+#[test]
+fn subcommands_require_their_arguments() {
+    let missing = |args: &[&str]| parse(args).unwrap_err().kind();
+
+    assert_eq!(missing(&["analyze"]), ErrorKind::MissingRequiredArgument);
+    assert_eq!(
+        missing(&["query", "--contract", "redbank"]),
+        ErrorKind::MissingRequiredArgument
+    );
+    assert_eq!(
+        missing(&["execute", "--json", "x.json"]),
+        ErrorKind::MissingRequiredArgument
+    );
+    assert_eq!(
+        missing(&[
+            "execute",
+            "--contract",
+            "x",
+            "--msg",
+            "{}",
+            "--wait",
+            "--simulate"
+        ]),
+        ErrorKind::ArgumentConflict
+    );
+    assert_eq!(
+        missing(&[
+            "execute",
+            "--contract",
+            "x",
+            "--msg",
+            "{}",
+            "--timeout",
+            "5"
+        ]),
+        ErrorKind::MissingRequiredArgument
+    );
+    assert_eq!(
+        missing(&[
+            "credit-account",
+            "--account",
+            "1",
+            "--lend",
+            "1uosmo",
+            "--poll-interval",
+            "1"
+        ]),
+        ErrorKind::MissingRequiredArgument
+    );
+    assert!(parse(&[
+        "execute",
+        "--contract",
+        "x",
+        "--msg",
+        "{}",
+        "--wait",
+        "--timeout",
+        "5"
+    ])
+    .is_ok());
+    assert!(parse(&["analyze", "ABC", "--raw-events", "--output", "json"]).is_ok());
+}
+
+#[test]
+fn output_parses_into_a_format() {
+    let output = |args: &[&str]| {
+        Cli::try_parse_from(["osmosis-cli-wrapper", "list"].iter().chain(args))
+            .map(|cli| cli.output)
+            .map_err(|e| e.kind())
+    };
+
+    assert_eq!(output(&[]), Ok(OutputFormat::Text));
+    assert_eq!(output(&["--output", "yaml"]), Ok(OutputFormat::Yaml));
+    assert_eq!(output(&["--output", "xml"]), Err(ErrorKind::InvalidValue));
+}
+
+#[test]
+fn completions_cover_every_subcommand() {
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let mut script = Vec::new();
+        clap_complete::generate(
+            shell,
+            &mut Cli::command(),
+            "osmosis-cli-wrapper",
+            &mut script,
+        );
+        let script = String::from_utf8(script).unwrap();
+
+        for subcommand in ["execute", "query", "analyze", "addr", "completions"] {
+            assert!(
+                script.contains(subcommand),
+                "{} lacks {}",
+                shell,
+                subcommand
+            );
+        }
+    }
+}
+
+#[test]
+fn page_size_must_be_positive() {
+    assert_eq!(
+        parse(&["markets", "--page-size", "0"]).unwrap_err().kind(),
+        ErrorKind::ValueValidation
+    );
+    assert_eq!(
+        parse(&["accounts", "--page-size", "0"]).unwrap_err().kind(),
+        ErrorKind::ValueValidation
+    );
+    assert!(parse(&["markets", "--page-size", "30"]).is_ok());
+}
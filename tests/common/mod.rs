@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+
+use osmosis_cli_wrapper::denom::DenomRegistry;
+use osmosis_cli_wrapper::network::{load_network, Network};
+use osmosis_cli_wrapper::runner::Fixtures;
+
+pub const RED_BANK: &str = "osmo1dl4rylasnd7mtfzlkdqn2gr0ss4gvyykpvr6d7t5ylzf6z535n9s5jjt8u";
+pub const USER: &str = "osmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yt";
+pub const OTHER: &str = "osmo1jv65s3grqf6v6jl3dp4t6c9t9rk99cd80yhvld";
+
+pub const TX_HASH: &str = "3F1A5D6C2B9E8F7A6D5C4B3A29180716F5E4D3C2B1A09F8E7D6C5B4A39281706";
+
+pub fn network() -> Network {
+    load_network("tests/fixtures/networks.toml", None).unwrap()
+}
+
+pub fn fixtures(name: &str) -> Fixtures {
+    Fixtures::load(&format!("tests/fixtures/{}.json", name)).unwrap()
+}
+
+pub fn denoms() -> DenomRegistry {
+    DenomRegistry::load(&network().denoms).unwrap()
+}
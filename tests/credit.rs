@@ -0,0 +1,187 @@
+mod common;
+
+use osmosis_cli_wrapper::cli::{credit_actions, Cli};
+use osmosis_cli_wrapper::credit::{
+    execute_credit_account, message_funds, Action, CreditManagerMsg, CREDIT_MANAGER, RED_BANK,
+};
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use osmosis_cli_wrapper::template::Vars;
+use osmosis_cli_wrapper::{compact_json, get_json, Fund};
+
+use clap::CommandFactory;
+
+use common::{denoms, fixtures, network};
+
+fn actions(args: &[&str]) -> osmosis_cli_wrapper::error::Result<Vec<Action>> {
+    let matches = Cli::command()
+        .try_get_matches_from(
+            ["osmosis-cli-wrapper", "credit-account", "--account", "37"]
+                .iter()
+                .chain(args),
+        )
+        .unwrap();
+    let (_, matches) = matches.subcommand().unwrap();
+
+    credit_actions(matches, &denoms())
+}
+
+fn coin(amount: &str, denom: &str) -> Fund {
+    Fund {
+        denom: denom.to_owned(),
+        amount: amount.to_owned(),
+    }
+}
+
+#[test]
+fn builds_the_same_message_as_the_template() {
+    let actions = actions(&[
+        "--deposit",
+        "10OSMO",
+        "--withdraw",
+        "1337uosmo",
+        "--borrow",
+        "1000000uosmo",
+    ])
+    .unwrap();
+    let msg = CreditManagerMsg::update_credit_account("37", actions).unwrap();
+
+    let template = get_json(
+        "json/creditManager/execute-deposit_withdraw_borrow.json",
+        &Vars::new(),
+        &denoms(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&msg).unwrap(),
+        compact_json(&template).unwrap()
+    );
+}
+
+#[test]
+fn deposits_become_the_funds_of_the_tx() {
+    let msg = CreditManagerMsg::update_credit_account(
+        "10",
+        vec![
+            Action::Deposit(coin("5", "uosmo")),
+            Action::Deposit(coin("7", "uion")),
+            Action::Deposit(coin("10", "uosmo")),
+            Action::Lend(coin("15", "uosmo")),
+        ],
+    )
+    .unwrap();
+
+    let funds = msg.funds().unwrap();
+
+    assert_eq!(funds.len(), 2);
+    assert_eq!(
+        (funds[0].amount.as_str(), funds[0].denom.as_str()),
+        ("7", "uion")
+    );
+    assert_eq!(
+        (funds[1].amount.as_str(), funds[1].denom.as_str()),
+        ("15", "uosmo")
+    );
+}
+
+#[test]
+fn deposits_are_attached_as_the_amount() {
+    let msg = CreditManagerMsg::update_credit_account(
+        "10",
+        vec![Action::Deposit(coin("10000000", "uosmo"))],
+    )
+    .unwrap();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    // The fixtures only know this message with `--amount=10000000uosmo`.
+    execute_credit_account(
+        &fixtures("execute"),
+        &network(),
+        &denoms(),
+        &msg,
+        false,
+        None,
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("\"code\": 0"));
+}
+
+#[test]
+fn deposits_go_first() {
+    let actions = actions(&["--borrow", "1uosmo", "--deposit", "10uosmo"]).unwrap();
+
+    let error = CreditManagerMsg::update_credit_account("37", actions).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "--deposit 10uosmo comes after a --borrow; deposits go first"
+    );
+}
+
+#[test]
+fn refuses_bad_amounts_and_accounts() {
+    let error = |args: &[&str]| actions(args).unwrap_err().to_string();
+
+    assert_eq!(
+        error(&["--deposit", "0OSMO"]),
+        "--deposit needs a positive amount, got 0OSMO"
+    );
+    assert_eq!(
+        error(&["--lend", "1uosmo,2uion"]),
+        "--lend takes a single coin, got 1uosmo,2uion"
+    );
+    assert_eq!(error(&["--borrow", "uosmo"]), "Invalid amount: uosmo");
+
+    let lend = vec![Action::Lend(coin("1", "uosmo"))];
+    assert_eq!(
+        CreditManagerMsg::update_credit_account("ten", lend)
+            .unwrap_err()
+            .to_string(),
+        "Invalid credit account id: ten"
+    );
+}
+
+#[test]
+fn credit_manager_deposits_decide_the_funds() {
+    let json = get_json(
+        "json/creditManager/execute-deposit_withdraw_borrow.json",
+        &Vars::new(),
+        &denoms(),
+    )
+    .unwrap();
+
+    let funds = message_funds(CREDIT_MANAGER, &json, None).unwrap();
+    assert_eq!(
+        (funds[0].amount.as_str(), funds[0].denom.as_str()),
+        ("10000000", "uosmo")
+    );
+
+    let agreeing = message_funds(CREDIT_MANAGER, &json, Some(vec![coin("10000000", "uosmo")]));
+    assert_eq!(agreeing.unwrap().len(), 1);
+
+    let error = message_funds(CREDIT_MANAGER, &json, Some(vec![coin("1", "uosmo")])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "--amount 1uosmo does not match the deposits of the creditManager message: 10000000uosmo"
+    );
+}
+
+#[test]
+fn redbank_deposits_need_an_amount() {
+    let deposit = r#"{"deposit":{}}"#;
+
+    assert!(message_funds(RED_BANK, deposit, None).is_err());
+    assert_eq!(
+        message_funds(RED_BANK, deposit, Some(vec![coin("5", "uion")]))
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(message_funds("oracle", r#"{"update_config":{}}"#, None)
+        .unwrap()
+        .is_empty());
+}
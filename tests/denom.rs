@@ -0,0 +1,125 @@
+mod common;
+
+use osmosis_cli_wrapper::denom::{parse_base_coins, to_base, to_display, DenomRegistry, DENOMS};
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::get_json;
+use osmosis_cli_wrapper::template::{render, Vars};
+
+use common::denoms;
+
+const ATOM: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+const MARS: &str = "factory/osmo1dl4rylasnd7mtfzlkdqn2gr0ss4gvyykpvr6d7t5ylzf6z535n9s5jjt8u/umars";
+
+#[test]
+fn display_amounts_convert_to_base_units() {
+    let coins = denoms()
+        .parse_coins("1.5OSMO,2 atom,0.000001MARS,10uion")
+        .unwrap();
+    let coins: Vec<(&str, &str)> = coins
+        .iter()
+        .map(|coin| (coin.amount.as_str(), coin.denom.as_str()))
+        .collect();
+
+    assert_eq!(
+        coins,
+        vec![
+            ("1500000", "uosmo"),
+            ("2000000", ATOM),
+            ("1", MARS),
+            ("10", "uion"),
+        ]
+    );
+}
+
+#[test]
+fn base_denoms_need_whole_amounts() {
+    let denoms = denoms();
+
+    assert!(matches!(
+        denoms.parse_coins("1.5uosmo"),
+        Err(Error::Config(_))
+    ));
+    assert!(matches!(
+        denoms.parse_coins("0.0000001OSMO"),
+        Err(Error::Config(_))
+    ));
+    assert!(matches!(denoms.parse_coins("OSMO"), Err(Error::Config(_))));
+    assert_eq!(
+        denoms.parse_coins("7uunknown").unwrap()[0].denom,
+        "uunknown"
+    );
+}
+
+#[test]
+fn base_coins_split_denoms() {
+    let funds = parse_base_coins("10uosmo, 5ibc/27394FB0").unwrap();
+
+    assert_eq!(funds.len(), 2);
+    assert_eq!(
+        (funds[0].amount.as_str(), funds[0].denom.as_str()),
+        ("10", "uosmo")
+    );
+    assert_eq!(
+        (funds[1].amount.as_str(), funds[1].denom.as_str()),
+        ("5", "ibc/27394FB0")
+    );
+    assert!(matches!(parse_base_coins("uosmo"), Err(Error::Config(_))));
+    assert!(matches!(parse_base_coins("10"), Err(Error::Config(_))));
+}
+
+#[test]
+fn amounts_round_trip_through_display_units() {
+    assert_eq!(to_base("1.5", 6).unwrap(), "1500000");
+    assert_eq!(to_base("0.000001", 6).unwrap(), "1");
+    assert_eq!(to_base("42", 0).unwrap(), "42");
+    assert_eq!(to_base(".", 6), None);
+
+    assert_eq!(to_display("1500000", 6).unwrap(), "1.5");
+    assert_eq!(to_display("-6250", 6).unwrap(), "-0.00625");
+    assert_eq!(to_display("2000000", 6).unwrap(), "2");
+
+    assert_eq!(denoms().display("1", MARS).unwrap(), "0.000001 MARS");
+    assert_eq!(denoms().display("1", "uunknown"), None);
+}
+
+#[test]
+fn templates_take_display_amounts() {
+    let mut vars = Vars::new();
+    vars.insert("amount".to_owned(), "2.5OSMO".to_owned());
+    vars.insert("denom".to_owned(), "OSMO".to_owned());
+
+    let json = get_json("json/creditManager/execute-deposit.json", &vars, &denoms()).unwrap();
+
+    assert!(json.contains(r#""denom": "uosmo""#));
+    assert!(json.contains(r#""amount": "2500000""#));
+}
+
+#[test]
+fn invalid_display_amounts_are_reported() {
+    let mut vars = Vars::new();
+    vars.insert("amount".to_owned(), "1.5uosmo".to_owned());
+
+    let unresolved = render(
+        r#"{"amount":"{{amount|amount}}"}"#,
+        &vars,
+        &Vars::new(),
+        &denoms(),
+    )
+    .unwrap_err();
+
+    assert_eq!(unresolved, vec!["amount (1.5uosmo is not an amount)"]);
+}
+
+#[test]
+fn shipped_registry_knows_the_ibc_tokens() {
+    let shipped = DenomRegistry::load(DENOMS).unwrap();
+
+    for symbol in ["OSMO", "ATOM", "USDC", "MARS"] {
+        assert!(shipped.by_symbol(symbol).is_some(), "{} is missing", symbol);
+    }
+    assert!(shipped
+        .parse_coin("1ATOM")
+        .unwrap()
+        .denom
+        .starts_with("ibc/"));
+}
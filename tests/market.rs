@@ -0,0 +1,83 @@
+mod common;
+
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::market::{markets_report, print_markets};
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use osmosis_cli_wrapper::runner::{Fixture, Fixtures};
+
+use std::fs;
+
+use common::{denoms, fixtures, network};
+
+#[test]
+fn markets_are_paged_and_priced() {
+    let runner = fixtures("markets");
+
+    // The fixture redbank returns at most 2 markets per page.
+    let report = markets_report(&runner, &network(), &denoms(), 3).unwrap();
+
+    let denoms: Vec<&str> = report
+        .markets
+        .iter()
+        .map(|market| market.denom.as_str())
+        .collect();
+    assert_eq!(denoms.len(), 3);
+    assert_eq!(&denoms[1..], ["uion", "uosmo"]);
+
+    let osmo = &report.markets[2];
+    assert_eq!(osmo.deposits, "30000000");
+    assert_eq!(osmo.borrows, "12000000");
+    assert!((osmo.utilization - 0.4).abs() < 1e-9);
+    // 30 OSMO at 1 uosmo per uosmo, with USDC at 2 uosmo per uusdc.
+    assert_eq!(osmo.tvl, Some(15.0));
+
+    assert_eq!(report.markets[0].price, None);
+    assert_eq!(report.tvl_unit, "USD");
+    assert_eq!(report.tvl, 21.25);
+}
+
+#[test]
+fn markets_without_a_usd_denom_use_the_oracle_base_denom() {
+    let runner = fixtures("markets");
+    let mut network = network();
+    network.usd_denom = None;
+
+    let report = markets_report(&runner, &network, &denoms(), 3).unwrap();
+
+    assert_eq!(report.tvl_unit, "OSMO");
+    assert_eq!(report.markets[2].tvl, Some(30.0));
+    assert_eq!(report.tvl, 42.5);
+}
+
+#[test]
+fn markets_fail_when_the_oracle_is_unreachable() {
+    let contents = fs::read_to_string("tests/fixtures/markets.json").unwrap();
+    let mut recorded: Vec<Fixture> = serde_json::from_str(&contents).unwrap();
+    for fixture in recorded.iter_mut().filter(|fixture| fixture.status != 0) {
+        fixture.stderr =
+            "Error: post failed: dial tcp 127.0.0.1:26657: connection refused".to_owned();
+    }
+    let runner = Fixtures::new(recorded);
+
+    let error = markets_report(&runner, &network(), &denoms(), 3).unwrap_err();
+
+    assert!(matches!(error, Error::Osmosisd { status: 1, .. }));
+}
+
+#[test]
+fn markets_print_a_row_per_market() {
+    let runner = fixtures("markets");
+    let report = markets_report(&runner, &network(), &denoms(), 3).unwrap();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    print_markets(&report, &mut out).unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(
+        "OSMO    30 OSMO   12 OSMO  40.00%       0.04         0.1         0.59     0.61            1      15.00"
+    ));
+    assert!(out.contains("ATOM    1 ATOM    0 ATOM   0.00%"));
+    assert!(out.contains("price  TVL (USD)"));
+    assert!(out.contains("TVL: 21.25 USD"));
+}
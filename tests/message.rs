@@ -0,0 +1,52 @@
+use osmosis_cli_wrapper::denom::DenomRegistry;
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::template::Vars;
+use osmosis_cli_wrapper::{read_message, MessageSource};
+
+#[test]
+fn file_messages_are_compacted() {
+    let msg = read_message(
+        &MessageSource::File("json/redbank/query-market.json".to_owned()),
+        &Vars::new(),
+        &DenomRegistry::default(),
+    )
+    .unwrap();
+
+    assert_eq!(msg, r#"{"market":{"denom":"uosmo"}}"#);
+}
+
+#[test]
+fn inline_messages_keep_key_order_and_take_vars() {
+    let mut vars = Vars::new();
+    vars.insert("account_id".to_owned(), "7".to_owned());
+
+    let msg = read_message(
+        &MessageSource::Inline(
+            r#"{ "owner_of": { "token_id": "{{account_id}}", "include_expired": false } }"#
+                .to_owned(),
+        ),
+        &vars,
+        &DenomRegistry::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        msg,
+        r#"{"owner_of":{"token_id":"7","include_expired":false}}"#
+    );
+}
+
+#[test]
+fn invalid_json_is_rejected() {
+    let error = read_message(
+        &MessageSource::Inline(r#"{"config": {}"#.to_owned()),
+        &Vars::new(),
+        &DenomRegistry::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Json { .. }));
+    assert!(error
+        .to_string()
+        .starts_with("Invalid JSON message from --msg"));
+}
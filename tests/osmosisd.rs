@@ -0,0 +1,288 @@
+mod common;
+
+use std::time::Duration;
+
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use osmosis_cli_wrapper::{
+    execute_tx, get_contract_address, get_tx_data, query_contract, simulate_tx, Wait,
+};
+
+use common::{denoms, fixtures, network, TX_HASH};
+
+const WAIT: Wait = Wait {
+    timeout: Duration::from_secs(5),
+    interval: Duration::ZERO,
+};
+
+#[test]
+fn query_prints_contract_response() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    query_contract(
+        &fixtures("query"),
+        &network,
+        get_contract_address(&network, "redbank").unwrap(),
+        r#"{"market":{"denom":"uosmo"}}"#.to_owned(),
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.starts_with("Input: \n {\"market\":{\"denom\":\"uosmo\"}}\n"));
+    assert!(out.contains("\"max_loan_to_value\": \"0.59\""));
+}
+
+#[test]
+fn query_fails_on_osmosisd_error() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    let error = query_contract(
+        &fixtures("query"),
+        &network,
+        get_contract_address(&network, "redbank").unwrap(),
+        r#"{"market":{"denom":"unknown"}}"#.to_owned(),
+        &mut out,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Osmosisd { status: 1, .. }));
+    assert!(error.to_string().contains("market not found"));
+    assert_eq!(error.exit_code(), 6);
+}
+
+#[test]
+fn unknown_contract_is_a_config_error() {
+    let error = get_contract_address(&network(), "nope").unwrap_err();
+
+    assert!(matches!(error, Error::Config(_)));
+    assert_eq!(error.exit_code(), 3);
+}
+
+#[test]
+fn execute_attaches_amount() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"update_credit_account":{"account_id":"10","actions":[{"deposit":{"denom":"uosmo","amount":"10000000"}}]}}"#.to_owned(),
+        "--amount=10000000uosmo".to_owned(),
+        None,
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(&format!("\"txhash\": \"{}\"", TX_HASH)));
+}
+
+#[test]
+fn execute_without_amount() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"create_credit_account":{}}"#.to_owned(),
+        "".to_owned(),
+        None,
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("\"code\": 0"));
+}
+
+#[test]
+fn execute_waits_for_inclusion() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"update_credit_account":{"account_id":"10","actions":[{"deposit":{"denom":"uosmo","amount":"10000000"}}]}}"#.to_owned(),
+        "--amount=10000000uosmo".to_owned(),
+        Some(WAIT),
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(&format!("___ Tx hash ___\n{}\n", TX_HASH)));
+    assert!(out.contains("--> wasm( _contract_address:"));
+}
+
+#[test]
+fn execute_wait_times_out() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    let error = execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"create_credit_account":{}}"#.to_owned(),
+        "".to_owned(),
+        Some(Wait {
+            timeout: Duration::ZERO,
+            interval: Duration::ZERO,
+        }),
+        &mut out,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Timeout { secs: 0, .. }));
+    assert_eq!(error.exit_code(), 8);
+}
+
+#[test]
+fn unknown_invocation_is_an_error() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    let result = query_contract(
+        &fixtures("query"),
+        &network,
+        get_contract_address(&network, "oracle").unwrap(),
+        r#"{"price":{"denom":"uosmo"}}"#.to_owned(),
+        &mut out,
+    );
+
+    assert!(matches!(result, Err(Error::Io { .. })));
+}
+
+#[test]
+fn analyze_decodes_events_and_names_contracts() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    get_tx_data(
+        &fixtures("analyze"),
+        &network,
+        &denoms(),
+        TX_HASH,
+        false,
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(&format!("___ Tx hash ___\n{}\n", TX_HASH)));
+    assert!(out.contains("___ Sender ___\nosmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yt (wallet)\n"));
+    assert!(out.contains(
+        "--> wasm( _contract_address: osmo15ywk53ck3wp6tnqgedfd8cnfx7fuhz9dr583hw8scp0xjgw46m0sf3kyyp (creditManager), action: callback/deposit"
+    ));
+    assert!(out.contains(
+        "--> coin_spent( spender: osmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yt (wallet), amount: 6250uosmo (0.00625 OSMO) )"
+    ));
+}
+
+#[test]
+fn analyze_fails_on_unknown_tx() {
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    let error = get_tx_data(
+        &fixtures("analyze"),
+        &network(),
+        &denoms(),
+        "DEADBEEF",
+        false,
+        &mut out,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Osmosisd { .. }));
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn execute_fails_on_chain_error() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    let error = execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"update_credit_account":{"account_id":"10","actions":[{"borrow":{"denom":"uosmo","amount":"1000000000000"}}]}}"#.to_owned(),
+        "".to_owned(),
+        None,
+        &mut out,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Chain { code: 5, .. }));
+    assert_eq!(error.exit_code(), 7);
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("___ Tx failed ___\n"));
+    assert!(out.contains("codespace: wasm\ncode: 5\n"));
+    assert!(out.contains("raw_log: failed to execute message; message index: 0"));
+}
+
+#[test]
+fn simulate_reports_gas_and_fee() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    simulate_tx(
+        &fixtures("execute"),
+        &network,
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"update_credit_account":{"account_id":"10","actions":[{"deposit":{"denom":"uosmo","amount":"10000000"}}]}}"#.to_owned(),
+        "--amount=10000000uosmo".to_owned(),
+        &mut out,
+    )
+    .unwrap();
+
+    let out = String::from_utf8(buf).unwrap();
+    assert!(
+        out.contains("___ Simulation ___\ngas estimate: 250036\nfee: 6251uosmo (at 0.025uosmo)\n")
+    );
+}
+
+#[test]
+fn simulate_surfaces_contract_error() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Text);
+
+    let error = simulate_tx(
+        &fixtures("execute"),
+        &network,
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"update_credit_account":{"account_id":"10","actions":[{"borrow":{"denom":"uosmo","amount":"1000000000000"}}]}}"#.to_owned(),
+        "".to_owned(),
+        &mut out,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Osmosisd { .. }));
+    assert!(error.to_string().contains("exceeds the account's max LTV"));
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use serde_json::Value;
+
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::output::{Output, OutputFormat};
+use osmosis_cli_wrapper::{execute_tx, get_contract_address, get_tx_data, query_contract};
+
+use common::{denoms, fixtures, network, TX_HASH};
+
+#[test]
+fn query_emits_only_the_contract_response() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Json);
+
+    query_contract(
+        &fixtures("query"),
+        &network,
+        get_contract_address(&network, "redbank").unwrap(),
+        r#"{"market":{"denom":"uosmo"}}"#.to_owned(),
+        &mut out,
+    )
+    .unwrap();
+
+    let json: Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(json["denom"], "uosmo");
+    assert_eq!(json["max_loan_to_value"], "0.59");
+}
+
+#[test]
+fn execute_emits_normalized_broadcast_result() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Yaml);
+
+    execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"create_credit_account":{}}"#.to_owned(),
+        "".to_owned(),
+        None,
+        &mut out,
+    )
+    .unwrap();
+
+    let yaml = String::from_utf8(buf).unwrap();
+    assert!(yaml.starts_with("txhash: 7B2E4C9A"));
+    assert!(yaml.contains("\ncode: 0\n"));
+    assert!(!yaml.contains("Input:"));
+}
+
+#[test]
+fn failed_execute_still_emits_the_result() {
+    let network = network();
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Json);
+
+    let error = execute_tx(
+        &fixtures("execute"),
+        &network,
+        &denoms(),
+        get_contract_address(&network, "creditManager").unwrap(),
+        r#"{"update_credit_account":{"account_id":"10","actions":[{"borrow":{"denom":"uosmo","amount":"1000000000000"}}]}}"#.to_owned(),
+        "".to_owned(),
+        None,
+        &mut out,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Chain { code: 5, .. }));
+
+    let json: Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(json["code"], 5);
+    assert_eq!(json["codespace"], "wasm");
+    assert_eq!(json["gas_used"], "412345");
+}
+
+#[test]
+fn analyze_emits_decoded_tx_document() {
+    let mut buf = Vec::new();
+    let mut out = Output::new(&mut buf, OutputFormat::Json);
+
+    get_tx_data(
+        &fixtures("analyze"),
+        &network(),
+        &denoms(),
+        TX_HASH,
+        false,
+        &mut out,
+    )
+    .unwrap();
+
+    let json: Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(json["hash"], TX_HASH);
+    assert_eq!(
+        json["sender"],
+        "osmo1xgyxa7nx0zg5ggggzkh2epdeaq33m4epwe84yt"
+    );
+    assert_eq!(json["sender_label"], "wallet");
+    assert_eq!(
+        json["messages"][0]["msg"]["update_credit_account"]["account_id"],
+        "10"
+    );
+
+    let wasm = json["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|event| event["type"] == "wasm")
+        .unwrap();
+    assert_eq!(wasm["attributes"][0]["key"], "_contract_address");
+    assert_eq!(wasm["attributes"][0]["label"], "creditManager");
+    assert_eq!(json["logs"][0]["msg_index"], 0);
+}
@@ -0,0 +1,90 @@
+use std::env;
+
+use osmosis_cli_wrapper::denom::DenomRegistry;
+use osmosis_cli_wrapper::error::Error;
+use osmosis_cli_wrapper::get_json;
+use osmosis_cli_wrapper::template::{parse_var, render, Vars};
+
+#[test]
+fn sidecar_defaults_fill_placeholders() {
+    let json = get_json(
+        "json/creditManager/execute-deposit.json",
+        &Vars::new(),
+        &DenomRegistry::default(),
+    )
+    .unwrap();
+
+    assert!(json.contains(r#""account_id": "10""#));
+    assert!(json.contains(r#""amount": "10000000""#));
+}
+
+#[test]
+fn vars_override_defaults() {
+    let mut vars = Vars::new();
+    vars.insert("account_id".to_owned(), "42".to_owned());
+
+    let json = get_json(
+        "json/creditManager/execute-deposit.json",
+        &vars,
+        &DenomRegistry::default(),
+    )
+    .unwrap();
+
+    assert!(json.contains(r#""account_id": "42""#));
+    assert!(json.contains(r#""denom": "uosmo""#));
+}
+
+#[test]
+fn environment_fills_placeholders() {
+    env::set_var("OSMOSIS_VAR_TEMPLATE_TEST_DENOM", "uion");
+
+    let rendered = render(
+        r#"{"denom":"{{template_test_denom}}"}"#,
+        &Vars::new(),
+        &Vars::new(),
+        &DenomRegistry::default(),
+    )
+    .unwrap();
+
+    assert_eq!(rendered, r#"{"denom":"uion"}"#);
+}
+
+#[test]
+fn unresolved_variables_are_listed() {
+    let unresolved = render(
+        r#"{"a":"{{first}}","b":"{{second}}","c":"{{first}}"}"#,
+        &Vars::new(),
+        &Vars::new(),
+        &DenomRegistry::default(),
+    )
+    .unwrap_err();
+
+    assert_eq!(unresolved, vec!["first", "second"]);
+}
+
+#[test]
+fn parse_var_rejects_missing_value() {
+    assert_eq!(
+        parse_var("user=osmo1abc").unwrap(),
+        ("user".to_owned(), "osmo1abc".to_owned())
+    );
+    assert!(matches!(parse_var("user"), Err(Error::Config(_))));
+}
+
+#[test]
+fn values_cannot_break_out_of_their_string() {
+    let mut vars = Vars::new();
+    vars.insert("user".to_owned(), r#"x", "limit": 1, "y": "\"#.to_owned());
+
+    let rendered = render(
+        r#"{"user_debt":{"user":"{{user}}"}}"#,
+        &vars,
+        &Vars::new(),
+        &DenomRegistry::default(),
+    )
+    .unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert_eq!(json["user_debt"].as_object().unwrap().len(), 1);
+    assert_eq!(json["user_debt"]["user"], vars["user"].as_str());
+}